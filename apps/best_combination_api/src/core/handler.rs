@@ -1,6 +1,6 @@
-use actix_web::{web::Data, HttpResponse};
+use actix_web::{web::Data, HttpRequest, HttpResponse, Result};
 use async_graphql::http::{self, GraphQLPlaygroundConfig};
-use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
 
 use super::resolver::AppSchema;
 
@@ -8,8 +8,19 @@ pub async fn index(schema: Data<AppSchema>, req: GraphQLRequest) -> GraphQLRespo
     schema.execute(req.into_inner()).await.into()
 }
 
+/// Serves the subscriptions over the graphql-ws protocol.
+pub async fn index_ws(
+    schema: Data<AppSchema>,
+    req: HttpRequest,
+    payload: actix_web::web::Payload,
+) -> Result<HttpResponse> {
+    GraphQLSubscription::new(AppSchema::clone(&schema)).start(&req, payload)
+}
+
 pub async fn index_playground() -> HttpResponse {
     HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
-        .body(http::playground_source(GraphQLPlaygroundConfig::new("/")))
+        .body(http::playground_source(
+            GraphQLPlaygroundConfig::new("/").subscription_endpoint("/"),
+        ))
 }
@@ -2,5 +2,5 @@ mod handler;
 mod resolver;
 mod service;
 
-pub use handler::{index, index_playground};
-pub use resolver::{Mutation, Query};
+pub use handler::{index, index_playground, index_ws};
+pub use resolver::{Mutation, Query, Subscription};
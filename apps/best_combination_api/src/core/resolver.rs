@@ -1,10 +1,14 @@
 use async_graphql::*;
+use futures::Stream;
 
-use libs::models::fetch_types::{FetchOptions, FetchResult, FetchStatus};
+use libs::models::{
+    dtos::CombinationDiffDto,
+    fetch_types::{FetchOptions, FetchResult, FetchStatus},
+};
 
 use super::service::{self, handle_request};
 
-pub type AppSchema = Schema<Query, Mutation, EmptySubscription>;
+pub type AppSchema = Schema<Query, Mutation, Subscription>;
 
 #[derive(Default)]
 pub struct Query;
@@ -19,6 +23,32 @@ impl Query {
     ) -> async_graphql::Result<FetchResult> {
         service::handle_request(ctx, input, opts).await
     }
+
+    /// Compares two combinations of a cached result, from the combination at index `from` to the
+    /// one at index `to`. The result has to be requested with `includeGameCoverage`.
+    async fn compare_best_combinations(
+        &self,
+        ctx: &Context<'_>,
+        input: Vec<String>,
+        opts: FetchOptions,
+        from: usize,
+        to: usize,
+    ) -> async_graphql::Result<CombinationDiffDto> {
+        service::handle_diff_request(ctx, input, opts, from, to).await
+    }
+
+    /// Fetches the Pareto frontier of the combined price versus the coverage of the games, or
+    /// versus their live coverage if `live` is set. The combinations are ranked by ascending price,
+    /// so they can be drawn as a trade-off curve. The `mode` of the options is overridden.
+    async fn get_pareto_frontier(
+        &self,
+        ctx: &Context<'_>,
+        input: Vec<String>,
+        opts: FetchOptions,
+        #[graphql(default)] live: bool,
+    ) -> async_graphql::Result<FetchResult> {
+        service::handle_pareto_request(ctx, input, opts, live).await
+    }
 }
 
 pub struct Mutation;
@@ -35,3 +65,20 @@ impl Mutation {
         Ok(result.status)
     }
 }
+
+pub struct Subscription;
+
+#[Subscription]
+impl Subscription {
+    /// Follows a request instead of polling `getBestCombination`. The request is enqueued if it
+    /// hasn't been yet. Pushes the current status of its job and every transition after it, and
+    /// completes once the best combinations are ready or the job has failed.
+    async fn best_combination_updates(
+        &self,
+        ctx: &Context<'_>,
+        input: Vec<String>,
+        opts: FetchOptions,
+    ) -> async_graphql::Result<impl Stream<Item = FetchResult>> {
+        service::handle_updates_subscription(ctx, input, opts).await
+    }
+}
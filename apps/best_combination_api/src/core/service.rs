@@ -1,14 +1,17 @@
 use std::sync::Arc;
 
 use async_graphql::*;
+use futures::{future, stream, Stream, StreamExt};
 
 use libs::{
-    caching::{self, CacheValue, CompositeKey, RedisClient},
+    caching::{self, CacheEntry, CacheValue, CompositeKey, RedisClient},
     db::dao::GameDao,
     messaging::{self, MqChannel},
     models::{
-        dtos::BestCombinationDto,
-        fetch_types::{FetchOptions, FetchResult, FetchStatus},
+        dtos::{BestCombinationDto, CombinationDiffDto, UncoveredGameDto},
+        fetch_types::{
+            FetchOptions, FetchResult, FetchStatus, JobProgress, SolverMode, UncoveredReason,
+        },
         payloads::TaskMessagePayload,
     },
 };
@@ -20,9 +23,66 @@ pub async fn handle_request(
     input: Vec<String>,
     opts: FetchOptions,
 ) -> async_graphql::Result<FetchResult> {
-    let game_dao = ctx.data::<Arc<GameDao>>()?;
+    let key = request_key(ctx, input, opts).await?;
+    fetch_or_enqueue(ctx, key).await
+}
+
+/// Follows the job of a request until it has completed or failed.
+///
+/// Subscribes to the updates of the request's cache entry before looking it up, so no transition
+/// is missed in between. The request is enqueued like any other if it hasn't been yet. The stream
+/// starts with the current state of the job and ends after its final state.
+///
+/// # Errors
+///
+/// Returns an error if the options of the request contradict each other, if its games are unknown,
+/// or if the updates can't be subscribed to.
+pub async fn handle_updates_subscription(
+    ctx: &Context<'_>,
+    input: Vec<String>,
+    opts: FetchOptions,
+) -> async_graphql::Result<impl Stream<Item = FetchResult>> {
     let redis_client = ctx.data::<Arc<RedisClient>>()?;
-    let mq_channel = ctx.data::<Arc<MqChannel>>()?;
+
+    let key = request_key(ctx, input, opts).await?;
+    let ids = key.ids.clone();
+    let updates =
+        caching::subscribe_updates::<CompositeKey, Vec<BestCombinationDto>>(redis_client, &key)
+            .await?
+            .filter_map(move |update| {
+                let result = match update {
+                    Ok(entry) => Some(fetch_result(ids.clone(), entry.value)),
+                    Err(e) => {
+                        log::warn!("Skipping a malformed update: {:?}", e);
+                        None
+                    }
+                };
+                future::ready(result)
+            });
+    let current = fetch_or_enqueue(ctx, key).await?;
+
+    // Ends the stream once the job has reached its final state
+    Ok(stream::once(future::ready(current))
+        .chain(updates)
+        .scan(false, |finished, result| {
+            if *finished {
+                return future::ready(None);
+            }
+            *finished = matches!(result.status, FetchStatus::Ready | FetchStatus::Error);
+            future::ready(Some(result))
+        }))
+}
+
+/// Validates the options of a request and resolves the games of the requested teams into its cache
+/// key.
+async fn request_key(
+    ctx: &Context<'_>,
+    input: Vec<String>,
+    opts: FetchOptions,
+) -> async_graphql::Result<CompositeKey> {
+    let game_dao = ctx.data::<Arc<GameDao>>()?;
+
+    opts.validate().map_err(|e| Error::new(e.to_string()))?;
 
     let game_ids = game_dao.aggregate_game_ids(input.clone()).await?;
 
@@ -34,52 +94,177 @@ pub async fn handle_request(
         )));
     }
 
-    let key = CompositeKey::new(game_ids.clone(), opts.clone());
+    Ok(CompositeKey::new(game_ids, opts))
+}
+
+/// Looks up the cached state of a request's job, and enqueues the job if there is none.
+async fn fetch_or_enqueue(
+    ctx: &Context<'_>,
+    key: CompositeKey,
+) -> async_graphql::Result<FetchResult> {
+    let game_dao = ctx.data::<Arc<GameDao>>()?;
+    let redis_client = ctx.data::<Arc<RedisClient>>()?;
+    let mq_channel = ctx.data::<Arc<MqChannel>>()?;
+    let game_ids = key.ids.clone();
 
     if let Some(cached_entry) =
         caching::get_cached_entry::<CompositeKey, Vec<BestCombinationDto>>(redis_client, &key)
             .await?
     {
-        match cached_entry.value {
-            CacheValue::Processing => {
-                return Ok(FetchResult {
-                    status: FetchStatus::Processing,
-                    ids: game_ids,
-                    data: None,
-                });
-            }
-            CacheValue::Data(data) => {
-                return Ok(FetchResult {
-                    status: FetchStatus::Ready,
-                    ids: game_ids,
-                    data: Some(data),
-                });
-            }
-        }
+        return Ok(fetch_result(game_ids, cached_entry.value));
+    }
+
+    let mut payload = TaskMessagePayload::from(key.clone());
+    if !payload.priorities.is_empty() {
+        let games = game_dao.find_games_by_ids(&payload.game_ids).await?;
+        payload.resolve_priorities(&games);
     }
 
     caching::cache_entry(
         redis_client,
         &key,
-        CacheValue::<Vec<BestCombinationDto>>::Processing,
+        CacheValue::<Vec<BestCombinationDto>>::Queued,
     )
     .await?;
 
-    let payload = TaskMessagePayload::from(key);
-    let job_enqueued = messaging::enqueue_job(mq_channel, &CONFIG.task_queue_name, &payload)
-        .await
-        .is_ok();
+    let job_queue = messaging::job_queue_name(&CONFIG.task_queue_name);
+    let value = match messaging::enqueue_job(mq_channel, &job_queue, &payload).await {
+        Ok(_) => CacheValue::Queued,
+        Err(e) => {
+            log::error!("Failed to enqueue job for payload: {:?}: {}", payload, e);
+            // Replaces the queued entry, so the request can be enqueued again once it expires
+            let reason = "The job couldn't be enqueued".to_string();
+            caching::cache_entry(
+                redis_client,
+                &key,
+                CacheValue::<Vec<BestCombinationDto>>::Failed {
+                    reason: reason.clone(),
+                },
+            )
+            .await?;
+            CacheValue::Failed { reason }
+        }
+    };
 
-    let status = if job_enqueued {
-        FetchStatus::Processing
+    Ok(fetch_result(game_ids, value))
+}
+
+/// Fetches the Pareto frontier of a request. It is computed like any other request, just with
+/// the frontier's solver mode, so it is queued and cached the same way.
+pub async fn handle_pareto_request(
+    ctx: &Context<'_>,
+    input: Vec<String>,
+    mut opts: FetchOptions,
+    live: bool,
+) -> async_graphql::Result<FetchResult> {
+    opts.mode = if live {
+        SolverMode::ParetoLiveCoverage
     } else {
-        log::error!("Failed to enqueue job for payload: {:?}", payload);
-        FetchStatus::Error
+        SolverMode::ParetoCoverage
+    };
+    handle_request(ctx, input, opts).await
+}
+
+/// Compares two combinations of the cached result of a request.
+///
+/// # Errors
+///
+/// Returns an error if the request doesn't include the coverage of its games, which the games
+/// are compared by, if its options contradict each other, if its games are unknown, if no result
+/// has been cached for it yet, or if it doesn't contain a combination at one of the indices.
+pub async fn handle_diff_request(
+    ctx: &Context<'_>,
+    input: Vec<String>,
+    opts: FetchOptions,
+    from: usize,
+    to: usize,
+) -> async_graphql::Result<CombinationDiffDto> {
+    let redis_client = ctx.data::<Arc<RedisClient>>()?;
+
+    if !opts.include_game_coverage {
+        return Err(Error::new(
+            "Combinations can only be compared if they include the coverage of their games",
+        ));
+    }
+
+    let key = request_key(ctx, input.clone(), opts).await?;
+
+    let cached_entry =
+        caching::get_cached_entry::<CompositeKey, Vec<BestCombinationDto>>(redis_client, &key)
+            .await?;
+    let Some(CacheEntry {
+        value: CacheValue::Completed(data),
+        ..
+    }) = cached_entry
+    else {
+        return Err(Error::new(format!(
+            "No result is ready for teams {:?}, fetch the best combination first",
+            input
+        )));
+    };
+
+    let combination = |index: usize| {
+        data.iter()
+            .find(|c| c.index == index)
+            .ok_or_else(|| Error::new(format!("The result has no combination {}", index)))
     };
 
-    Ok(FetchResult {
-        status,
-        ids: game_ids,
+    Ok(CombinationDiffDto::new(
+        combination(from)?,
+        combination(to)?,
+    ))
+}
+
+/// Maps the cached state of a job to the result reported to the client.
+fn fetch_result(ids: Vec<usize>, value: CacheValue<Vec<BestCombinationDto>>) -> FetchResult {
+    let mut result = FetchResult {
+        status: FetchStatus::Queued,
+        ids,
         data: None,
-    })
+        partial: false,
+        unavailable_games: Vec::new(),
+        progress: None,
+        failure_reason: None,
+    };
+
+    match value {
+        CacheValue::Queued => {}
+        CacheValue::Running {
+            explored_nodes,
+            best_cost_so_far,
+        } => {
+            result.status = FetchStatus::Running;
+            result.progress = Some(JobProgress {
+                explored_nodes,
+                best_cost_so_far,
+            });
+        }
+        CacheValue::Completed(data) => {
+            result.status = FetchStatus::Ready;
+            result.partial = data.iter().any(|c| c.timed_out);
+            result.unavailable_games = unavailable_games(&data);
+            result.data = Some(data);
+        }
+        CacheValue::Failed { reason } => {
+            result.status = FetchStatus::Error;
+            result.failure_reason = Some(reason);
+        }
+    }
+
+    result
+}
+
+/// Collects the requested games which no package offers or the options of the request rule out.
+/// They are the same for every combination of a result, so they are taken from the first one.
+fn unavailable_games(data: &[BestCombinationDto]) -> Vec<UncoveredGameDto> {
+    data.first()
+        .map(|combination| {
+            combination
+                .uncovered_games
+                .iter()
+                .filter(|g| g.reason != UncoveredReason::NotInCombination)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
 }
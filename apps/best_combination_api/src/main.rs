@@ -2,12 +2,13 @@ use std::{io, sync::Arc};
 
 use actix_cors::Cors;
 use actix_web::{
+    guard,
     web::{self, Data},
     App, HttpServer,
 };
-use async_graphql::{EmptySubscription, Schema};
+use async_graphql::Schema;
 
-use best_combination_api::{Mutation, Query, CONFIG};
+use best_combination_api::{Mutation, Query, Subscription, CONFIG};
 use libs::{
     caching,
     constants::{DATABASE_NAME, GAME_COLLECTION_NAME},
@@ -32,7 +33,7 @@ async fn main() -> io::Result<()> {
     let mongo_client = MongoClient::init(&CONFIG.mongodb_uri, DATABASE_NAME).await;
     let game_dao = GameDao::new(mongo_client.get_collection(GAME_COLLECTION_NAME));
 
-    let schema = Schema::build(Query, Mutation, EmptySubscription)
+    let schema = Schema::build(Query, Mutation, Subscription)
         .data(Arc::new(mq_channel.clone()))
         .data(Arc::new(redis_client.clone()))
         .data(Arc::new(game_dao))
@@ -46,6 +47,11 @@ async fn main() -> io::Result<()> {
             .service(
                 web::resource("/")
                     .route(web::post().to(best_combination_api::index))
+                    .route(
+                        web::get()
+                            .guard(guard::Header("upgrade", "websocket"))
+                            .to(best_combination_api::index_ws),
+                    )
                     .route(web::get().to(best_combination_api::index_playground)),
             )
             .route("/metrics", web::get().to(metrics::metrics_handler))
@@ -1,8 +1,40 @@
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 
-fn default_use_yearly_price() -> bool {
-    false
+fn default_solver_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_solver_node_budget() -> usize {
+    5_000_000
+}
+
+fn default_solver_threads() -> usize {
+    1
+}
+
+pub(crate) fn default_solver() -> String {
+    "recursive".to_string()
+}
+
+pub(crate) fn default_warm_start_max_distance() -> f64 {
+    0.2
+}
+
+fn default_worker_metrics_port() -> u16 {
+    8003
+}
+
+fn default_job_retry_limit() -> u32 {
+    3
+}
+
+fn default_job_retry_base_delay_ms() -> u64 {
+    1000
+}
+
+fn default_job_retry_max_delay_ms() -> u64 {
+    30_000
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -11,8 +43,24 @@ pub struct Config {
     pub redis_url: String,
     pub rabbitmq_url: String,
     pub task_queue_name: String,
-    #[serde(default = "default_use_yearly_price")]
-    pub use_yearly_price: bool,
+    #[serde(default = "default_solver_timeout_ms")]
+    pub solver_timeout_ms: u64,
+    #[serde(default = "default_solver_node_budget")]
+    pub solver_node_budget: usize,
+    #[serde(default = "default_solver_threads")]
+    pub solver_threads: usize,
+    #[serde(default = "default_solver")]
+    pub default_solver: String,
+    #[serde(default = "default_warm_start_max_distance")]
+    pub warm_start_max_distance: f64,
+    #[serde(default = "default_worker_metrics_port")]
+    pub worker_metrics_port: u16,
+    #[serde(default = "default_job_retry_limit")]
+    pub job_retry_limit: u32,
+    #[serde(default = "default_job_retry_base_delay_ms")]
+    pub job_retry_base_delay_ms: u64,
+    #[serde(default = "default_job_retry_max_delay_ms")]
+    pub job_retry_max_delay_ms: u64,
 }
 
 pub static CONFIG: Lazy<Config> = Lazy::new(|| {
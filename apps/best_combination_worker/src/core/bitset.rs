@@ -0,0 +1,274 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use libs::models::dtos::BestCombinationSubsetDto;
+
+use super::weights::GameWeights;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed-width set of dense indices, stored as 64-bit words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    /// Creates an empty set able to hold the indices `0..len`.
+    pub fn new(len: usize) -> Self {
+        BitSet {
+            words: vec![0; len.div_ceil(WORD_BITS)],
+        }
+    }
+
+    pub fn insert(&mut self, index: usize) {
+        self.words[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+    }
+
+    /// Returns the number of indices in the set.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns the number of indices in `self` which aren't in `other`, without allocating.
+    pub fn count_difference(&self, other: &Self) -> usize {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & !b).count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns the sum of the `weights` of the indices in `self` which aren't in `other`, without
+    /// allocating.
+    pub fn weigh_difference(&self, other: &Self, weights: &[usize]) -> usize {
+        let mut sum = 0;
+        for (w, (a, b)) in self.words.iter().zip(&other.words).enumerate() {
+            let mut word = a & !b;
+            while word != 0 {
+                sum += weights[w * WORD_BITS + word.trailing_zeros() as usize];
+                word &= word - 1;
+            }
+        }
+        sum
+    }
+
+    /// Returns the indices in `self` which aren't in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        BitSet {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| a & !b)
+                .collect(),
+        }
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a |= b);
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a &= !b);
+    }
+}
+
+/// The universe of a request remapped to dense indices, with every subset as a [`BitSet`] over them.
+///
+/// Games which aren't part of the universe are dropped from the subsets, as they can't contribute
+/// to a cover. Each game carries its weight from the [`GameWeights`] of the request.
+#[derive(Debug, Clone)]
+pub struct CoverIndex {
+    universe_len: usize,
+    subsets: Vec<BitSet>,
+    /// The weight of each game, or `None` if all games weigh `1`.
+    weights: Option<Vec<usize>>,
+}
+
+impl CoverIndex {
+    pub fn new(
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        weights: &GameWeights,
+    ) -> Self {
+        let indices: BTreeMap<usize, usize> = universe
+            .iter()
+            .enumerate()
+            .map(|(index, &game_id)| (game_id, index))
+            .collect();
+
+        let subsets = subsets
+            .iter()
+            .map(|s| {
+                let mut bitset = BitSet::new(universe.len());
+                s.elements
+                    .iter()
+                    .filter_map(|e| indices.get(&e.game_id))
+                    .for_each(|&index| bitset.insert(index));
+                bitset
+            })
+            .collect();
+
+        // A game without any weight would never be worth covering in the greedy ratio
+        let weights: Vec<usize> = universe
+            .iter()
+            .map(|&id| weights.weight(id).max(1))
+            .collect();
+
+        CoverIndex {
+            universe_len: universe.len(),
+            subsets,
+            weights: weights.iter().any(|&w| w != 1).then_some(weights),
+        }
+    }
+
+    pub fn universe_len(&self) -> usize {
+        self.universe_len
+    }
+
+    /// Returns the games of the `i`-th subset, in the order the subsets have been passed in.
+    pub fn subset(&self, i: usize) -> &BitSet {
+        &self.subsets[i]
+    }
+
+    /// Returns an empty set over the universe.
+    pub fn empty(&self) -> BitSet {
+        BitSet::new(self.universe_len)
+    }
+}
+
+/// The games covered by a partial solution over a [`CoverIndex`], maintained incrementally.
+#[derive(Debug, Clone)]
+pub struct Coverage<'a> {
+    index: &'a CoverIndex,
+    covered: BitSet,
+    covered_count: usize,
+}
+
+impl<'a> Coverage<'a> {
+    pub fn new(index: &'a CoverIndex) -> Self {
+        Coverage {
+            index,
+            covered: index.empty(),
+            covered_count: 0,
+        }
+    }
+
+    /// Whether every game of the universe is covered.
+    pub fn is_complete(&self) -> bool {
+        self.covered_count == self.index.universe_len()
+    }
+
+    /// Returns the number of games of the `i`-th subset which aren't covered yet.
+    pub fn fresh_count(&self, i: usize) -> usize {
+        self.index.subset(i).count_difference(&self.covered)
+    }
+
+    /// Returns the summed weight of the games of the `i`-th subset which aren't covered yet, which
+    /// is the [`Coverage::fresh_count`] if all games weigh `1`.
+    pub fn fresh_weight(&self, i: usize) -> usize {
+        match &self.index.weights {
+            Some(weights) => self
+                .index
+                .subset(i)
+                .weigh_difference(&self.covered, weights),
+            None => self.fresh_count(i),
+        }
+    }
+
+    /// Adds the games of the `i`-th subset.
+    ///
+    /// # Returns
+    ///
+    /// The newly covered games, which have to be passed to [`Coverage::undo`] when backtracking.
+    pub fn add(&mut self, i: usize) -> BitSet {
+        let fresh = self.index.subset(i).difference(&self.covered);
+        self.covered.union_with(&fresh);
+        self.covered_count += fresh.count();
+        fresh
+    }
+
+    pub fn undo(&mut self, fresh: &BitSet) {
+        self.covered.difference_with(fresh);
+        self.covered_count -= fresh.count();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libs::models::dtos::BestCombinationElementDto;
+
+    #[test]
+    fn test_cover_index() {
+        let universe: BTreeSet<usize> = (0..100).map(|id| id * 10).collect();
+        let subsets = vec![BestCombinationSubsetDto::new(
+            1,
+            "S1",
+            [0, 650, 990, 5]
+                .iter()
+                .map(|&game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                .collect(),
+            Some(5),
+            5,
+        )];
+
+        let index = CoverIndex::new(&universe, &subsets, &GameWeights::default());
+        let subset = index.subset(0);
+
+        assert_eq!(index.universe_len(), 100);
+        assert_eq!(subset.count(), 3);
+
+        let mut expected = index.empty();
+        [0, 65, 99].iter().for_each(|&i| expected.insert(i));
+        assert_eq!(*subset, expected);
+
+        let mut coverage = Coverage::new(&index);
+        assert_eq!(coverage.fresh_count(0), 3);
+        assert!(!coverage.is_complete());
+
+        let fresh = coverage.add(0);
+        assert_eq!(fresh, expected);
+        assert_eq!(coverage.fresh_count(0), 0);
+
+        coverage.undo(&fresh);
+        assert_eq!(coverage.fresh_count(0), 3);
+    }
+
+    #[test]
+    fn test_weighted_coverage() {
+        let universe: BTreeSet<usize> = (0..100).collect();
+        let subset = |id, game_ids: &[usize]| {
+            BestCombinationSubsetDto::new(
+                id,
+                &format!("S{}", id),
+                game_ids
+                    .iter()
+                    .map(|&game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                    .collect(),
+                Some(5),
+                5,
+            )
+        };
+        let subsets = vec![subset(1, &[1, 64, 70, 99]), subset(2, &[64])];
+        let weights = GameWeights::new(BTreeMap::from([(64, 3), (99, 5), (50, 7)]), &[]);
+
+        let index = CoverIndex::new(&universe, &subsets, &weights);
+        let mut coverage = Coverage::new(&index);
+        assert_eq!(coverage.fresh_count(0), 4);
+        assert_eq!(coverage.fresh_weight(0), 10);
+
+        coverage.add(1);
+        assert_eq!(coverage.fresh_count(0), 3);
+        assert_eq!(coverage.fresh_weight(0), 7);
+
+        let unweighted = CoverIndex::new(&universe, &subsets, &GameWeights::default());
+        assert_eq!(Coverage::new(&unweighted).fresh_weight(0), 4);
+    }
+}
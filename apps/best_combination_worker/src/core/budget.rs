@@ -0,0 +1,668 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use libs::{models::dtos::BestCombinationSubsetDto, solver::SearchLimits};
+
+use super::weights::GameWeights;
+
+/// The outcome of a budgeted maximum coverage search.
+///
+/// `covered_weight` is the combined weight of the covered games. `proven_optimal` is `true` if the
+/// search space has been exhausted, i.e. no combination within the budget covers more weight, or
+/// as much weight for less.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetSolution {
+    pub package_ids: Vec<usize>,
+    pub cost: usize,
+    pub covered_weight: usize,
+    pub proven_optimal: bool,
+}
+
+/// A streaming package prepared for the search, with its elements remapped to dense indices.
+struct Candidate {
+    package_id: usize,
+    cost: usize,
+    elements: Vec<usize>,
+    weight: usize,
+}
+
+/// A branch-and-bound solver for the budgeted maximum coverage problem.
+///
+/// # Overview
+///
+/// The solver looks for the combinations which cover the most weight of the universe while their
+/// combined cost stays within the budget and, if set, their number of packages within
+/// [`max_packages`](Self::max_packages). Combinations covering the same weight are ranked by their
+/// cost, so if the whole universe can be covered, the cheapest covers come first. Combinations
+/// which miss a must-cover game are never returned.
+///
+/// The search starts from a greedy combination. The candidates are ordered by their cost per weight
+/// and the search decides for each of them whether it is taken or not, taking it first. A candidate
+/// is only taken if it covers a game which isn't covered yet, and only combinations without
+/// redundant packages are recorded. A node is pruned if one of its uncovered must-cover games can
+/// no longer be covered within the budget, or if an upper bound for its coverage cannot beat the
+/// worst of the `k` recorded combinations. The bound is the fractional knapsack relaxation over the
+/// newly covered weight of the remaining candidates, which overestimates the coverage as overlaps
+/// between them are ignored. With a package cap, the bound is further limited to the largest newly
+/// covered weights of as many candidates as can still be taken.
+///
+/// [Forced packages](BudgetSolver::new) are part of every combination. Their games
+/// count as covered and their prices and slots are taken off the budget and the package cap before
+/// the search starts, so they are never dropped as redundant.
+pub struct BudgetSolver {
+    candidates: Vec<Candidate>,
+    /// Candidate indices covering each element.
+    covering: Vec<Vec<usize>>,
+    element_weights: Vec<usize>,
+    must_cover: Vec<bool>,
+    total_weight: usize,
+    budget: usize,
+    max_packages: usize,
+    /// The packages every combination contains, with their combined cost and covered weight.
+    forced_package_ids: Vec<usize>,
+    forced_cost: usize,
+    forced_weight: usize,
+    /// Whether a must-cover game of the universe isn't offered by any affordable candidate, or the
+    /// forced packages can't be booked within the budget.
+    infeasible: bool,
+}
+
+impl BudgetSolver {
+    /// Prepares the search over `subsets` for the given universe and budget. Every combination
+    /// contains the packages of `forced_package_ids`, so only the games they leave uncovered are
+    /// searched for, with the budget they leave. If they can't be booked within the budget, no
+    /// combination is returned.
+    ///
+    /// Duplicate package IDs are only considered once, and packages which can't be booked with
+    /// their billing option, exceed the budget on their own or don't cover any game of the
+    /// universe are skipped.
+    pub fn new(
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        budget: usize,
+        weights: &GameWeights,
+        forced_package_ids: &[usize],
+    ) -> Self {
+        let mut forced: Vec<&BestCombinationSubsetDto> = Vec::new();
+        for subset in subsets {
+            if forced_package_ids.contains(&subset.streaming_package_id)
+                && !forced
+                    .iter()
+                    .any(|f| f.streaming_package_id == subset.streaming_package_id)
+            {
+                forced.push(subset);
+            }
+        }
+
+        let forced_cost: Option<usize> = forced.iter().map(|s| s.billed_price_cents()).sum();
+        let forced_games: BTreeSet<usize> = forced
+            .iter()
+            .flat_map(|s| s.element_ids())
+            .filter(|id| universe.contains(id))
+            .collect();
+        let universe: BTreeSet<usize> = universe - &forced_games;
+        let (budget, forced_affordable) = match forced_cost {
+            Some(cost) if cost <= budget => (budget - cost, true),
+            _ => (0, false),
+        };
+
+        let mut element_indices: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut candidates: Vec<Candidate> = Vec::new();
+
+        for subset in subsets {
+            if forced_package_ids.contains(&subset.streaming_package_id)
+                || candidates
+                    .iter()
+                    .any(|c| c.package_id == subset.streaming_package_id)
+            {
+                continue;
+            }
+
+            let Some(cost) = subset.billed_price_cents().filter(|&cost| cost <= budget) else {
+                continue;
+            };
+
+            let elements: Vec<usize> = subset
+                .elements
+                .iter()
+                .filter(|e| universe.contains(&e.game_id))
+                .map(|e| {
+                    let next_index = element_indices.len();
+                    *element_indices.entry(e.game_id).or_insert(next_index)
+                })
+                .collect();
+
+            if !elements.is_empty() {
+                let weight = subset
+                    .element_ids()
+                    .iter()
+                    .filter(|id| universe.contains(id))
+                    .map(|&id| weights.weight(id))
+                    .sum();
+
+                candidates.push(Candidate {
+                    package_id: subset.streaming_package_id,
+                    cost,
+                    elements,
+                    weight,
+                });
+            }
+        }
+
+        candidates.sort_by(|a, b| {
+            let ratio_a = a.cost as f64 / a.weight as f64;
+            let ratio_b = b.cost as f64 / b.weight as f64;
+            ratio_a
+                .total_cmp(&ratio_b)
+                .then(a.package_id.cmp(&b.package_id))
+        });
+
+        let mut covering = vec![Vec::new(); element_indices.len()];
+        for (i, candidate) in candidates.iter().enumerate() {
+            for &e in &candidate.elements {
+                covering[e].push(i);
+            }
+        }
+
+        let mut element_weights = vec![0; element_indices.len()];
+        let mut must_cover = vec![false; element_indices.len()];
+        for (&game_id, &e) in &element_indices {
+            element_weights[e] = weights.weight(game_id);
+            must_cover[e] = weights.is_must_cover(game_id);
+        }
+
+        let infeasible = !forced_affordable
+            || universe
+                .iter()
+                .any(|&id| weights.is_must_cover(id) && !element_indices.contains_key(&id));
+
+        BudgetSolver {
+            candidates,
+            covering,
+            total_weight: element_weights.iter().sum(),
+            element_weights,
+            must_cover,
+            budget,
+            max_packages: usize::MAX,
+            forced_package_ids: forced.iter().map(|s| s.streaming_package_id).collect(),
+            forced_cost: forced_cost.unwrap_or(0),
+            forced_weight: forced_games.iter().map(|&id| weights.weight(id)).sum(),
+            infeasible,
+        }
+    }
+
+    /// Sets the maximum number of packages per combination, including the forced ones.
+    pub fn max_packages(mut self, max_packages: usize) -> Self {
+        self.max_packages = max_packages;
+        self
+    }
+
+    /// Returns up to `k` distinct combinations within the budget, ordered by descending covered
+    /// weight and then by ascending cost and package IDs. If the must-cover games can't be covered
+    /// within the budget, no combination is returned.
+    ///
+    /// If the search `limits` are exhausted, the best combinations found so far are returned with
+    /// `proven_optimal` set to `false`.
+    pub fn solve(&self, k: usize, limits: &SearchLimits) -> Vec<BudgetSolution> {
+        if k == 0 || self.infeasible || self.forced_package_ids.len() > self.max_packages {
+            return Vec::new();
+        }
+
+        let mut search = Search {
+            solver: self,
+            limits,
+            k,
+            cover_count: vec![0; self.element_weights.len()],
+            covered: 0,
+            uncovered_must: self.must_cover.iter().filter(|&&m| m).count(),
+            chosen: Vec::new(),
+            cost: 0,
+            best: Vec::new(),
+            timed_out: false,
+        };
+        // The forced packages alone form a combination, which every other one extends
+        if !self.forced_package_ids.is_empty() && search.uncovered_must == 0 {
+            search.record();
+        }
+        search.greedy();
+        search.run(0);
+
+        search
+            .best
+            .into_iter()
+            .map(|(Reverse(covered_weight), cost, mut package_ids)| {
+                package_ids.extend(self.forced_package_ids.iter().copied());
+                package_ids.sort();
+                BudgetSolution {
+                    package_ids,
+                    cost: cost + self.forced_cost,
+                    covered_weight: covered_weight + self.forced_weight,
+                    proven_optimal: !search.timed_out,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A recorded combination, ordered from best to worst.
+type Ranked = (Reverse<usize>, usize, Vec<usize>);
+
+/// The mutable state of a single branch-and-bound run.
+struct Search<'a> {
+    solver: &'a BudgetSolver,
+    limits: &'a SearchLimits,
+    k: usize,
+    /// How many chosen candidates cover each element.
+    cover_count: Vec<usize>,
+    /// The combined weight of the covered elements.
+    covered: usize,
+    uncovered_must: usize,
+    chosen: Vec<usize>,
+    cost: usize,
+    best: Vec<Ranked>,
+    timed_out: bool,
+}
+
+impl Search<'_> {
+    fn choose(&mut self, candidate: usize) {
+        for &e in &self.solver.candidates[candidate].elements {
+            if self.cover_count[e] == 0 {
+                self.covered += self.solver.element_weights[e];
+                if self.solver.must_cover[e] {
+                    self.uncovered_must -= 1;
+                }
+            }
+            self.cover_count[e] += 1;
+        }
+        self.cost += self.solver.candidates[candidate].cost;
+        self.chosen.push(candidate);
+    }
+
+    fn unchoose(&mut self, candidate: usize) {
+        for &e in &self.solver.candidates[candidate].elements {
+            self.cover_count[e] -= 1;
+            if self.cover_count[e] == 0 {
+                self.covered -= self.solver.element_weights[e];
+                if self.solver.must_cover[e] {
+                    self.uncovered_must += 1;
+                }
+            }
+        }
+        self.cost -= self.solver.candidates[candidate].cost;
+        self.chosen.pop();
+    }
+
+    /// The weight of the currently uncovered elements of a candidate.
+    fn fresh_weight(&self, candidate: usize) -> usize {
+        self.solver.candidates[candidate]
+            .elements
+            .iter()
+            .filter(|&&e| self.cover_count[e] == 0)
+            .map(|&e| self.solver.element_weights[e])
+            .sum()
+    }
+
+    fn covers_uncovered_must(&self, candidate: usize) -> bool {
+        self.solver.candidates[candidate]
+            .elements
+            .iter()
+            .any(|&e| self.cover_count[e] == 0 && self.solver.must_cover[e])
+    }
+
+    /// The number of packages which can still be taken without exceeding the package cap.
+    fn remaining_slots(&self) -> usize {
+        self.solver.max_packages - self.solver.forced_package_ids.len() - self.chosen.len()
+    }
+
+    /// Whether a candidate can be taken without exceeding the budget or the package cap.
+    fn fits_budget(&self, candidate: usize) -> bool {
+        self.remaining_slots() > 0
+            && self.cost + self.solver.candidates[candidate].cost <= self.solver.budget
+    }
+
+    /// Whether every uncovered must-cover element can still be covered by an affordable candidate
+    /// from `next` onwards.
+    fn can_cover_must(&self, next: usize) -> bool {
+        (0..self.cover_count.len())
+            .filter(|&e| self.solver.must_cover[e] && self.cover_count[e] == 0)
+            .all(|e| {
+                self.solver.covering[e]
+                    .iter()
+                    .any(|&c| c >= next && self.fits_budget(c))
+            })
+    }
+
+    /// A combination is irredundant if every chosen candidate covers a game on its own.
+    fn is_irredundant(&self) -> bool {
+        self.chosen.iter().all(|&c| {
+            self.solver.candidates[c]
+                .elements
+                .iter()
+                .any(|&e| self.cover_count[e] == 1)
+        })
+    }
+
+    /// Records an initial combination by taking the candidates in order of their cost per weight as
+    /// long as they fit into the budget, starting with the ones covering must-cover games. This way
+    /// a usable answer is available even if the search limits are exhausted right away.
+    fn greedy(&mut self) {
+        for candidate in 0..self.solver.candidates.len() {
+            if self.fits_budget(candidate) && self.covers_uncovered_must(candidate) {
+                self.choose(candidate);
+            }
+        }
+        for candidate in 0..self.solver.candidates.len() {
+            if self.fits_budget(candidate) && self.fresh_weight(candidate) > 0 {
+                self.choose(candidate);
+            }
+        }
+
+        if !self.chosen.is_empty() && self.uncovered_must == 0 && self.is_irredundant() {
+            self.record();
+        }
+
+        while let Some(&candidate) = self.chosen.last() {
+            self.unchoose(candidate);
+        }
+    }
+
+    fn record(&mut self) {
+        let mut package_ids: Vec<usize> = self
+            .chosen
+            .iter()
+            .map(|&c| self.solver.candidates[c].package_id)
+            .collect();
+        package_ids.sort();
+
+        if self.best.iter().any(|(_, _, ids)| *ids == package_ids) {
+            return;
+        }
+
+        self.best
+            .push((Reverse(self.covered), self.cost, package_ids));
+        self.best.sort();
+        self.best.truncate(self.k);
+    }
+
+    /// Computes an upper bound for the weight the candidates from `next` onwards can additionally
+    /// cover with the remaining budget and package slots.
+    fn upper_bound(&self, next: usize) -> usize {
+        let remaining_slots = self.remaining_slots();
+        let remaining_budget = self.solver.budget - self.cost;
+        let mut options: Vec<(usize, usize)> = (next..self.solver.candidates.len())
+            .map(|c| (self.fresh_weight(c), self.solver.candidates[c].cost))
+            .filter(|&(fresh, cost)| fresh > 0 && cost <= remaining_budget)
+            .collect();
+
+        let mut fresh_weights: Vec<usize> = options.iter().map(|&(fresh, _)| fresh).collect();
+        fresh_weights.sort_by(|a, b| b.cmp(a));
+        let slots_bound: usize = fresh_weights.into_iter().take(remaining_slots).sum();
+
+        options.sort_by(|a, b| (b.0 as f64 / b.1 as f64).total_cmp(&(a.0 as f64 / a.1 as f64)));
+
+        let mut budget = remaining_budget as f64;
+        let mut bound = 0.0;
+        for (fresh, cost) in options {
+            if cost as f64 <= budget {
+                budget -= cost as f64;
+                bound += fresh as f64;
+            } else {
+                bound += fresh as f64 * budget / cost as f64;
+                break;
+            }
+        }
+
+        // Add a small epsilon to stay admissible despite floating point rounding
+        let bound = (bound + 1e-6).floor() as usize;
+        bound
+            .min(slots_bound)
+            .min(self.solver.total_weight - self.covered)
+    }
+
+    /// Whether no combination below this node can make it into the `k` best ones.
+    fn can_prune(&self, next: usize) -> bool {
+        let Some((Reverse(worst_covered), worst_cost, _)) = self.best.last() else {
+            return false;
+        };
+        if self.best.len() < self.k {
+            return false;
+        }
+
+        let bound = self.covered + self.upper_bound(next);
+        bound < *worst_covered || (bound == *worst_covered && self.cost >= *worst_cost)
+    }
+
+    fn run(&mut self, next: usize) {
+        if !self.limits.explore() {
+            self.timed_out = true;
+            return;
+        }
+
+        if !self.can_cover_must(next) || self.can_prune(next) {
+            return;
+        }
+
+        if next == self.solver.candidates.len() || self.covered == self.solver.total_weight {
+            return;
+        }
+
+        if self.fits_budget(next) && self.fresh_weight(next) > 0 {
+            self.choose(next);
+            if self.uncovered_must == 0 && self.is_irredundant() {
+                self.record();
+            }
+            self.run(next + 1);
+            self.unchoose(next);
+        }
+
+        self.run(next + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use libs::models::dtos::BestCombinationElementDto;
+
+    fn subset(id: usize, game_ids: &[usize], cost: usize) -> BestCombinationSubsetDto {
+        BestCombinationSubsetDto::new(
+            id,
+            &format!("S{}", id),
+            game_ids
+                .iter()
+                .map(|&game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                .collect(),
+            Some(cost),
+            cost,
+        )
+    }
+
+    fn limits() -> SearchLimits {
+        SearchLimits::new(Duration::from_secs(10), usize::MAX)
+    }
+
+    fn ranking(solutions: &[BudgetSolution]) -> Vec<(Vec<usize>, usize, usize)> {
+        solutions
+            .iter()
+            .map(|s| (s.package_ids.clone(), s.covered_weight, s.cost))
+            .collect()
+    }
+
+    #[test]
+    fn test_budget_maximizes_coverage() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3, 4, 5, 6]);
+        let subsets = vec![
+            subset(1, &[1, 2, 3, 4], 30),
+            subset(2, &[1, 2, 3], 10),
+            subset(3, &[4, 5], 10),
+            subset(4, &[6], 15),
+        ];
+
+        let solutions = BudgetSolver::new(&universe, &subsets, 20, &GameWeights::default(), &[])
+            .solve(3, &limits());
+
+        assert_eq!(
+            ranking(&solutions),
+            vec![(vec![2, 3], 5, 20), (vec![2], 3, 10), (vec![3], 2, 10)]
+        );
+        assert!(solutions.iter().all(|s| s.proven_optimal));
+    }
+
+    #[test]
+    fn test_budget_prefers_cheaper_combination_on_equal_coverage() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2]);
+        let subsets = vec![
+            subset(1, &[1, 2], 20),
+            subset(2, &[1], 5),
+            subset(3, &[2], 5),
+        ];
+
+        let solutions = BudgetSolver::new(&universe, &subsets, 20, &GameWeights::default(), &[])
+            .solve(2, &limits());
+
+        assert_eq!(
+            ranking(&solutions),
+            vec![(vec![2, 3], 2, 10), (vec![1], 2, 20)]
+        );
+    }
+
+    #[test]
+    fn test_budget_maximizes_weighted_coverage() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3]);
+        let subsets = vec![subset(1, &[1, 2], 10), subset(2, &[3], 10)];
+        let weights = GameWeights::new(BTreeMap::from([(3, 5)]), &[]);
+
+        let solutions =
+            BudgetSolver::new(&universe, &subsets, 10, &weights, &[]).solve(2, &limits());
+
+        assert_eq!(
+            ranking(&solutions),
+            vec![(vec![2], 5, 10), (vec![1], 2, 10)]
+        );
+    }
+
+    #[test]
+    fn test_budget_enforces_must_cover() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+        let subsets = vec![
+            subset(1, &[1, 2, 3], 10),
+            subset(2, &[4], 10),
+            subset(3, &[3, 4], 15),
+        ];
+        let weights = GameWeights::new(BTreeMap::new(), &[4]);
+
+        let solutions =
+            BudgetSolver::new(&universe, &subsets, 20, &weights, &[]).solve(3, &limits());
+
+        assert_eq!(
+            ranking(&solutions),
+            vec![(vec![1, 2], 4, 20), (vec![3], 2, 15), (vec![2], 1, 10)]
+        );
+
+        // The must-cover game isn't offered by any affordable package
+        let solutions =
+            BudgetSolver::new(&universe, &subsets, 5, &weights, &[]).solve(1, &limits());
+        assert!(solutions.is_empty());
+    }
+
+    #[test]
+    fn test_budget_skips_unaffordable_packages() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2]);
+        let subsets = vec![subset(1, &[1, 2], 50), subset(2, &[1], 60)];
+
+        let solutions = BudgetSolver::new(&universe, &subsets, 40, &GameWeights::default(), &[])
+            .solve(1, &limits());
+
+        assert!(solutions.is_empty());
+    }
+
+    #[test]
+    fn test_budget_max_packages() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+        let subsets = vec![
+            subset(1, &[1], 5),
+            subset(2, &[2], 5),
+            subset(3, &[3, 4], 5),
+            subset(4, &[1, 2], 20),
+        ];
+
+        let solver = BudgetSolver::new(
+            &universe,
+            &subsets,
+            usize::MAX,
+            &GameWeights::default(),
+            &[],
+        );
+        let capped = solver.max_packages(2).solve(2, &limits());
+
+        assert_eq!(
+            ranking(&capped),
+            vec![(vec![3, 4], 4, 25), (vec![1, 3], 3, 10)]
+        );
+        assert!(capped.iter().all(|s| s.proven_optimal));
+
+        let solver = BudgetSolver::new(
+            &universe,
+            &subsets,
+            usize::MAX,
+            &GameWeights::default(),
+            &[],
+        );
+        let partial = solver.max_packages(1).solve(1, &limits());
+
+        assert_eq!(ranking(&partial), vec![(vec![3], 2, 5)]);
+    }
+
+    #[test]
+    fn test_budget_deadline_returns_best_so_far() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3]);
+        let subsets = vec![subset(1, &[1, 2], 5), subset(2, &[3], 5)];
+
+        let solutions = BudgetSolver::new(&universe, &subsets, 10, &GameWeights::default(), &[])
+            .solve(1, &SearchLimits::new(Duration::ZERO, usize::MAX));
+
+        assert_eq!(ranking(&solutions), vec![(vec![1, 2], 3, 10)]);
+        assert!(!solutions[0].proven_optimal);
+    }
+
+    #[test]
+    fn test_budget_keeps_forced_packages() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3]);
+        let subsets = vec![
+            subset(1, &[1, 2, 3], 10),
+            subset(2, &[1], 8),
+            subset(3, &[2, 3], 5),
+        ];
+
+        // S2 is redundant next to S1, but forced
+        let solutions = BudgetSolver::new(&universe, &subsets, 20, &GameWeights::default(), &[2])
+            .solve(2, &limits());
+        assert_eq!(
+            ranking(&solutions),
+            vec![(vec![2, 3], 3, 13), (vec![1, 2], 3, 18)]
+        );
+
+        // The forced package takes the only slot
+        let solutions = BudgetSolver::new(&universe, &subsets, 20, &GameWeights::default(), &[2])
+            .max_packages(1)
+            .solve(1, &limits());
+        assert_eq!(ranking(&solutions), vec![(vec![2], 1, 8)]);
+
+        // The forced package doesn't fit into the budget
+        let solutions = BudgetSolver::new(&universe, &subsets, 7, &GameWeights::default(), &[2])
+            .solve(1, &limits());
+        assert!(solutions.is_empty());
+    }
+}
@@ -0,0 +1,935 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, BinaryHeap},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use libs::{models::dtos::BestCombinationSubsetDto, solver::SearchLimits};
+use rayon::prelude::*;
+
+/// Number of levels of the search tree whose branches are searched in parallel.
+const PARALLEL_SPLIT_DEPTH: usize = 3;
+
+/// The outcome of an exact set cover search.
+///
+/// `proven_optimal` is `true` if the search space has been exhausted, i.e. no cheaper cover
+/// exists. If the search limits have been exhausted first, the solution is the best cover found so
+/// far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExactSolution {
+    pub package_ids: Vec<usize>,
+    pub cost: usize,
+    pub proven_optimal: bool,
+}
+
+/// A streaming package prepared for the search, with its elements remapped to dense indices.
+struct Candidate {
+    package_id: usize,
+    cost: usize,
+    elements: Vec<usize>,
+}
+
+/// A branch-and-bound solver for the weighted set cover problem.
+///
+/// # Overview
+///
+/// The solver covers every game of the universe that is offered by at least one bookable package,
+/// so universes without a full cover still yield the cheapest cover of the coverable part.
+///
+/// At each node it branches on the uncovered game with the fewest remaining candidates. Once a
+/// candidate has been explored for that game, it is excluded from its sibling branches, so every
+/// combination is visited at most once. A node is pruned if its cost plus a lower bound for the
+/// uncovered games cannot beat the incumbent. The lower bound is the larger of:
+///
+/// - the sum over all uncovered games of the cheapest cost-per-uncovered-game of a package
+///   covering it (every cover pays at least this share for each game), and
+/// - the cheapest package covering the most expensive uncovered game.
+///
+/// The search starts from a greedy incumbent, so a usable answer is available even if the
+/// search limits are exhausted right away. If a [warm start](ExactSolver::warm_start) is given and
+/// its repaired cover is cheaper, that one becomes the incumbent instead.
+///
+/// In parallel mode, the branches of the first [`PARALLEL_SPLIT_DEPTH`] levels of the search tree
+/// are searched on the rayon thread pool, so a large first branch is split up as well instead of
+/// serializing the search. All branches share the cost of the cheapest cover found so far to prune
+/// nodes which can't reach it, but only prune nodes which can't beat their own incumbent with
+/// equal costs. Every branch therefore still finds its first cheapest cover, and merging the
+/// branches of each level in order yields the same cover as the sequential search.
+pub struct ExactSolver {
+    candidates: Vec<Candidate>,
+    /// Candidate indices covering each element.
+    covering: Vec<Vec<usize>>,
+    parallel: bool,
+    /// Candidate indices of the warm start cover.
+    warm_start: Vec<usize>,
+    report_costs: bool,
+}
+
+impl ExactSolver {
+    /// Prepares the search over `subsets` for the given universe, using the billed prices.
+    ///
+    /// Duplicate package IDs are only considered once, and packages which can't be booked with
+    /// their billing option or don't cover any game of the universe are skipped.
+    pub fn new(universe: &BTreeSet<usize>, subsets: &[BestCombinationSubsetDto]) -> Self {
+        ExactSolver::with_pricing(
+            universe,
+            subsets,
+            BestCombinationSubsetDto::billed_price_cents,
+        )
+    }
+
+    /// Prepares the search like [`ExactSolver::new`], but prices the packages with `price`. Packages
+    /// for which it returns `None` are skipped.
+    pub fn with_pricing(
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        price: impl Fn(&BestCombinationSubsetDto) -> Option<usize>,
+    ) -> Self {
+        let mut element_indices: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut candidates: Vec<Candidate> = Vec::new();
+
+        for subset in subsets {
+            if candidates
+                .iter()
+                .any(|c| c.package_id == subset.streaming_package_id)
+            {
+                continue;
+            }
+
+            let Some(cost) = price(subset) else {
+                continue;
+            };
+
+            let elements: Vec<usize> = subset
+                .elements
+                .iter()
+                .filter(|e| universe.contains(&e.game_id))
+                .map(|e| {
+                    let next_index = element_indices.len();
+                    *element_indices.entry(e.game_id).or_insert(next_index)
+                })
+                .collect();
+
+            if !elements.is_empty() {
+                candidates.push(Candidate {
+                    package_id: subset.streaming_package_id,
+                    cost,
+                    elements,
+                });
+            }
+        }
+
+        let mut covering = vec![Vec::new(); element_indices.len()];
+        for (i, candidate) in candidates.iter().enumerate() {
+            for &e in &candidate.elements {
+                covering[e].push(i);
+            }
+        }
+
+        ExactSolver {
+            candidates,
+            covering,
+            parallel: false,
+            warm_start: Vec::new(),
+            report_costs: false,
+        }
+    }
+
+    /// Sets whether the branches of the first levels of the search tree are searched in parallel.
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Seeds the searches with the cover of a similar universe, given by its package IDs.
+    ///
+    /// The cover is repaired into a cover of this universe before each search: its packages which
+    /// are still candidates are kept, the games they leave uncovered are covered greedily, and
+    /// packages which became redundant are dropped again. Packages which aren't candidates are
+    /// ignored.
+    pub fn warm_start(mut self, package_ids: &[usize]) -> Self {
+        self.warm_start = package_ids
+            .iter()
+            .filter_map(|&id| self.candidates.iter().position(|c| c.package_id == id))
+            .collect();
+        self
+    }
+
+    /// Sets whether the costs of the covers found are recorded in the search limits, see
+    /// [`SearchLimits::record_cost`]. Only enable it if the packages are priced by their billed
+    /// prices, as the recorded costs are reported as the progress of the request.
+    pub fn report_costs(mut self, report_costs: bool) -> Self {
+        self.report_costs = report_costs;
+        self
+    }
+
+    /// Enumerates the `k` cheapest distinct covers in ascending order of cost.
+    ///
+    /// This uses Lawler's partitioning scheme: after a cover `{s1, ..., sm}` has been taken from the
+    /// queue, its solution space is split into `m` disjoint subproblems, where the `i`-th subproblem
+    /// requires `s1, ..., s(i-1)` and bans `si`. Each subproblem is solved to optimality and queued by
+    /// cost, so the next cheapest cover is always at the front of the queue. Ties are broken by the
+    /// sorted package IDs to keep the ranking deterministic.
+    ///
+    /// Since a cover is only partitioned on its own packages, covers which merely add packages to a
+    /// previously returned cover are never enumerated. A cover can still contain redundant packages,
+    /// e.g. required ones next to a free package or a cut short search's incumbent. Such a cover is
+    /// [reduced to its irredundant core](ExactSolver::irredundant) before it is returned, and skipped
+    /// if that core has been returned already, while its solution space is still partitioned. So
+    /// stripping redundant packages from the solutions afterwards never merges any of them, and
+    /// fewer than `k` solutions are only returned if there are no more distinct irredundant covers.
+    ///
+    /// All subproblems share the same `limits`. Once a search has been cut short, the ranking can
+    /// no longer be proven and the remaining solutions are flagged accordingly.
+    pub fn solve_k_best(&self, k: usize, limits: &SearchLimits) -> Vec<ExactSolution> {
+        let mut subproblems: Vec<Subproblem> = Vec::new();
+        let mut queue: SubproblemQueue = BinaryHeap::new();
+        let mut all_proven = true;
+        let mut solutions = Vec::new();
+
+        if let Some(root) = self.solve_constrained(&[], &[], limits) {
+            enqueue(&mut queue, &mut subproblems, root);
+        }
+
+        while solutions.len() < k {
+            let Some(Reverse((_, _, i))) = queue.pop() else {
+                break;
+            };
+            let subproblem = subproblems[i].clone();
+            all_proven &= subproblem.proven_optimal;
+
+            let cover = self.irredundant(&subproblem.cover);
+            let mut package_ids: Vec<usize> = cover
+                .iter()
+                .map(|&c| self.candidates[c].package_id)
+                .collect();
+            package_ids.sort();
+            if solutions
+                .iter()
+                .all(|s: &ExactSolution| s.package_ids != package_ids)
+            {
+                solutions.push(ExactSolution {
+                    package_ids,
+                    cost: cover.iter().map(|&c| self.candidates[c].cost).sum(),
+                    proven_optimal: all_proven,
+                });
+            }
+
+            if solutions.len() == k {
+                break;
+            }
+
+            let free: Vec<usize> = subproblem
+                .cover
+                .iter()
+                .copied()
+                .filter(|c| !subproblem.required.contains(c))
+                .collect();
+
+            for (j, &candidate) in free.iter().enumerate() {
+                let mut required = subproblem.required.clone();
+                required.extend_from_slice(&free[..j]);
+                let mut banned = subproblem.banned.clone();
+                banned.push(candidate);
+
+                if let Some(child) = self.solve_constrained(&required, &banned, limits) {
+                    all_proven &= child.proven_optimal;
+                    enqueue(&mut queue, &mut subproblems, child);
+                }
+            }
+        }
+
+        solutions
+    }
+
+    /// Drops the candidates of a cover whose elements are all covered by its other candidates as
+    /// well, the most expensive ones first and ties by their package IDs, like
+    /// [`strip_redundant_packages`](super::redundancy::strip_redundant_packages) does.
+    fn irredundant(&self, cover: &[usize]) -> Vec<usize> {
+        let mut cover = cover.to_vec();
+        cover.sort_by_key(|&c| {
+            (
+                Reverse(self.candidates[c].cost),
+                self.candidates[c].package_id,
+            )
+        });
+
+        let mut cover_count = vec![0; self.covering.len()];
+        for &c in &cover {
+            for &e in &self.candidates[c].elements {
+                cover_count[e] += 1;
+            }
+        }
+
+        cover.retain(|&c| {
+            let redundant = self.candidates[c]
+                .elements
+                .iter()
+                .all(|&e| cover_count[e] > 1);
+            if redundant {
+                for &e in &self.candidates[c].elements {
+                    cover_count[e] -= 1;
+                }
+            }
+            !redundant
+        });
+        cover
+    }
+
+    /// Searches for the cheapest cover which contains all `required` and none of the `banned`
+    /// candidates. Returns `None` if no such cover exists.
+    fn solve_constrained(
+        &self,
+        required: &[usize],
+        banned: &[usize],
+        limits: &SearchLimits,
+    ) -> Option<Subproblem> {
+        let mut search = Search::new(self, limits);
+        for &candidate in banned {
+            search.banned[candidate] = true;
+        }
+        for &candidate in required {
+            search.choose(candidate);
+        }
+
+        search.best = search.greedy_cover();
+        if !self.warm_start.is_empty() {
+            if let Some((cover, cost)) = search.repair_cover(&self.warm_start) {
+                if search
+                    .best
+                    .as_ref()
+                    .is_none_or(|(_, best_cost)| cost < *best_cost)
+                {
+                    search.best = Some((cover, cost));
+                }
+            }
+        }
+        if let (true, Some((_, cost))) = (self.report_costs, &search.best) {
+            limits.record_cost(*cost);
+        }
+        if self.parallel {
+            search.run_parallel(PARALLEL_SPLIT_DEPTH);
+        } else {
+            search.run();
+        }
+
+        let (cover, cost) = search.best?;
+        let mut package_ids: Vec<usize> = cover
+            .iter()
+            .map(|&c| self.candidates[c].package_id)
+            .collect();
+        package_ids.sort();
+
+        Some(Subproblem {
+            cover,
+            cost,
+            package_ids,
+            required: required.to_vec(),
+            banned: banned.to_vec(),
+            proven_optimal: !search.timed_out,
+        })
+    }
+}
+
+/// A node of Lawler's partitioning: the constraints of a subproblem and its optimal cover.
+#[derive(Clone)]
+struct Subproblem {
+    cover: Vec<usize>,
+    cost: usize,
+    package_ids: Vec<usize>,
+    required: Vec<usize>,
+    banned: Vec<usize>,
+    proven_optimal: bool,
+}
+
+/// Min-queue of subproblem indices, ordered by cost and then by package IDs.
+type SubproblemQueue = BinaryHeap<Reverse<(usize, Vec<usize>, usize)>>;
+
+fn enqueue(queue: &mut SubproblemQueue, subproblems: &mut Vec<Subproblem>, subproblem: Subproblem) {
+    queue.push(Reverse((
+        subproblem.cost,
+        subproblem.package_ids.clone(),
+        subproblems.len(),
+    )));
+    subproblems.push(subproblem);
+}
+
+/// The mutable state of a single branch-and-bound run.
+#[derive(Clone)]
+struct Search<'a> {
+    solver: &'a ExactSolver,
+    limits: &'a SearchLimits,
+    /// The cheapest cost found by any branch of a parallel search.
+    shared_best: Option<&'a AtomicUsize>,
+    /// How many chosen candidates cover each element.
+    cover_count: Vec<usize>,
+    uncovered: usize,
+    banned: Vec<bool>,
+    chosen: Vec<usize>,
+    cost: usize,
+    best: Option<(Vec<usize>, usize)>,
+    timed_out: bool,
+}
+
+impl<'a> Search<'a> {
+    fn new(solver: &'a ExactSolver, limits: &'a SearchLimits) -> Self {
+        Search {
+            solver,
+            limits,
+            shared_best: None,
+            cover_count: vec![0; solver.covering.len()],
+            uncovered: solver.covering.len(),
+            banned: vec![false; solver.candidates.len()],
+            chosen: Vec::new(),
+            cost: 0,
+            best: None,
+            timed_out: false,
+        }
+    }
+
+    fn choose(&mut self, candidate: usize) {
+        for &e in &self.solver.candidates[candidate].elements {
+            if self.cover_count[e] == 0 {
+                self.uncovered -= 1;
+            }
+            self.cover_count[e] += 1;
+        }
+        self.cost += self.solver.candidates[candidate].cost;
+        self.chosen.push(candidate);
+    }
+
+    fn unchoose(&mut self, candidate: usize) {
+        for &e in &self.solver.candidates[candidate].elements {
+            self.cover_count[e] -= 1;
+            if self.cover_count[e] == 0 {
+                self.uncovered += 1;
+            }
+        }
+        self.cost -= self.solver.candidates[candidate].cost;
+        self.chosen.pop();
+    }
+
+    /// Number of currently uncovered elements for every candidate.
+    fn fresh_counts(&self) -> Vec<usize> {
+        self.solver
+            .candidates
+            .iter()
+            .map(|c| {
+                c.elements
+                    .iter()
+                    .filter(|&&e| self.cover_count[e] == 0)
+                    .count()
+            })
+            .collect()
+    }
+
+    /// Builds an initial cover by repeatedly picking the candidate with the best cost per newly
+    /// covered element.
+    fn greedy_cover(&mut self) -> Option<(Vec<usize>, usize)> {
+        let base = self.chosen.len();
+
+        while self.uncovered > 0 {
+            let fresh = self.fresh_counts();
+            let next = (0..self.solver.candidates.len())
+                .filter(|&i| !self.banned[i] && fresh[i] > 0)
+                .min_by(|&a, &b| {
+                    let ratio_a = self.solver.candidates[a].cost as f64 / fresh[a] as f64;
+                    let ratio_b = self.solver.candidates[b].cost as f64 / fresh[b] as f64;
+                    ratio_a.total_cmp(&ratio_b)
+                });
+
+            match next {
+                Some(candidate) => self.choose(candidate),
+                None => break,
+            }
+        }
+
+        let result = (self.uncovered == 0).then(|| (self.chosen.clone(), self.cost));
+
+        while self.chosen.len() > base {
+            let candidate = self.chosen[self.chosen.len() - 1];
+            self.unchoose(candidate);
+        }
+
+        result
+    }
+
+    /// Repairs the cover of a similar universe into a cover of the current node: the `seed`
+    /// candidates which aren't banned are chosen, the elements they leave uncovered are covered
+    /// greedily, and seed candidates which only cover elements other chosen candidates cover as
+    /// well are dropped again, the most expensive ones first.
+    fn repair_cover(&mut self, seed: &[usize]) -> Option<(Vec<usize>, usize)> {
+        let base = self.chosen.len();
+
+        for &candidate in seed {
+            if !self.banned[candidate] && !self.chosen.contains(&candidate) {
+                self.choose(candidate);
+            }
+        }
+        let seeded = self.chosen[base..].to_vec();
+        let repaired = self.greedy_cover();
+
+        while self.chosen.len() > base {
+            let candidate = self.chosen[self.chosen.len() - 1];
+            self.unchoose(candidate);
+        }
+
+        let (mut cover, mut cost) = repaired?;
+        let mut cover_count = vec![0; self.solver.covering.len()];
+        for &candidate in &cover {
+            for &e in &self.solver.candidates[candidate].elements {
+                cover_count[e] += 1;
+            }
+        }
+
+        let mut removable = seeded;
+        removable.sort_by_key(|&c| Reverse(self.solver.candidates[c].cost));
+        for candidate in removable {
+            let elements = &self.solver.candidates[candidate].elements;
+            if elements.iter().all(|&e| cover_count[e] > 1) {
+                elements.iter().for_each(|&e| cover_count[e] -= 1);
+                cover.retain(|&c| c != candidate);
+                cost -= self.solver.candidates[candidate].cost;
+            }
+        }
+
+        Some((cover, cost))
+    }
+
+    /// Computes a lower bound for the cost of covering all uncovered elements, or `None` if an
+    /// uncovered element can no longer be covered by any candidate.
+    fn lower_bound(&self, fresh: &[usize]) -> Option<usize> {
+        let mut ratio_sum = 0.0;
+        let mut max_min_cost = 0;
+
+        for (e, covering) in self.solver.covering.iter().enumerate() {
+            if self.cover_count[e] > 0 {
+                continue;
+            }
+
+            let available = covering.iter().filter(|&&c| !self.banned[c]);
+            let min_ratio = available
+                .clone()
+                .map(|&c| self.solver.candidates[c].cost as f64 / fresh[c] as f64)
+                .min_by(f64::total_cmp)?;
+            let min_cost = available.map(|&c| self.solver.candidates[c].cost).min()?;
+
+            ratio_sum += min_ratio;
+            max_min_cost = max_min_cost.max(min_cost);
+        }
+
+        // Subtract a small epsilon to stay admissible despite floating point rounding
+        let ratio_bound = (ratio_sum - 1e-6).ceil().max(0.0) as usize;
+        Some(ratio_bound.max(max_min_cost))
+    }
+
+    /// Explores the current node. Returns the candidates to branch on, or `None` if the node has been
+    /// settled.
+    fn expand(&mut self) -> Option<Vec<usize>> {
+        if !self.limits.explore() {
+            self.timed_out = true;
+            return None;
+        }
+
+        if self.uncovered == 0 {
+            if self.best.as_ref().is_none_or(|(_, cost)| self.cost < *cost) {
+                self.best = Some((self.chosen.clone(), self.cost));
+                if self.solver.report_costs {
+                    self.limits.record_cost(self.cost);
+                }
+                if let Some(shared_best) = self.shared_best {
+                    shared_best.fetch_min(self.cost, Ordering::Relaxed);
+                }
+            }
+            return None;
+        }
+
+        let fresh = self.fresh_counts();
+        let bound = self.lower_bound(&fresh)?; // Infeasible branch
+        if let Some((_, best_cost)) = &self.best {
+            if self.cost + bound >= *best_cost {
+                return None;
+            }
+        }
+        if let Some(shared_best) = self.shared_best {
+            if self.cost + bound > shared_best.load(Ordering::Relaxed) {
+                return None;
+            }
+        }
+
+        // Branch on the uncovered element with the fewest available candidates
+        let element = (0..self.solver.covering.len())
+            .filter(|&e| self.cover_count[e] == 0)
+            .min_by_key(|&e| {
+                self.solver.covering[e]
+                    .iter()
+                    .filter(|&&c| !self.banned[c])
+                    .count()
+            })?;
+
+        let mut options: Vec<usize> = self.solver.covering[element]
+            .iter()
+            .copied()
+            .filter(|&c| !self.banned[c])
+            .collect();
+        options.sort_by(|&a, &b| {
+            let ratio_a = self.solver.candidates[a].cost as f64 / fresh[a] as f64;
+            let ratio_b = self.solver.candidates[b].cost as f64 / fresh[b] as f64;
+            ratio_a.total_cmp(&ratio_b)
+        });
+
+        Some(options)
+    }
+
+    fn run(&mut self) {
+        let Some(options) = self.expand() else {
+            return;
+        };
+
+        let mut newly_banned = Vec::with_capacity(options.len());
+        for candidate in options {
+            self.choose(candidate);
+            self.run();
+            self.unchoose(candidate);
+
+            if self.timed_out {
+                break;
+            }
+
+            // Later siblings must not pick this candidate again
+            self.banned[candidate] = true;
+            newly_banned.push(candidate);
+        }
+
+        for candidate in newly_banned {
+            self.banned[candidate] = false;
+        }
+    }
+
+    /// Explores the current node like [`Search::run`], but searches its branches in parallel and
+    /// merges their results in the order of the sequential search. The branches are split up again
+    /// until `depth` levels have been searched in parallel.
+    fn run_parallel(&mut self, depth: usize) {
+        let Some(options) = self.expand() else {
+            return;
+        };
+
+        // Nested levels share the cheapest cost of the whole search
+        let local_best;
+        let shared_best = match self.shared_best {
+            Some(shared_best) => shared_best,
+            None => {
+                local_best = AtomicUsize::new(self.best.as_ref().map_or(usize::MAX, |(_, c)| *c));
+                &local_best
+            }
+        };
+        let branches: Vec<Search> = options
+            .iter()
+            .enumerate()
+            .map(|(i, &candidate)| {
+                let mut branch = self.clone();
+                branch.shared_best = Some(shared_best);
+                // Later siblings must not pick the candidates of the earlier ones again
+                for &sibling in &options[..i] {
+                    branch.banned[sibling] = true;
+                }
+                branch.choose(candidate);
+                branch
+            })
+            .collect();
+
+        let branches: Vec<Search> = branches
+            .into_par_iter()
+            .map(|mut branch| {
+                if depth > 1 {
+                    branch.run_parallel(depth - 1);
+                } else {
+                    branch.run();
+                }
+                branch
+            })
+            .collect();
+
+        for branch in branches {
+            self.timed_out |= branch.timed_out;
+            if let Some((cover, cost)) = branch.best {
+                if self
+                    .best
+                    .as_ref()
+                    .is_none_or(|(_, best_cost)| cost < *best_cost)
+                {
+                    self.best = Some((cover, cost));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use libs::models::dtos::BestCombinationElementDto;
+
+    fn subset(id: usize, game_ids: &[usize], cost: usize) -> BestCombinationSubsetDto {
+        BestCombinationSubsetDto::new(
+            id,
+            &format!("S{}", id),
+            game_ids
+                .iter()
+                .map(|&game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                .collect(),
+            Some(cost),
+            cost,
+        )
+    }
+
+    /// Games 1 to 6, where the greedy cover {1, 4, 5} costs 80 and the optimal cover {2, 3} 66.
+    fn greedy_trap() -> (BTreeSet<usize>, Vec<BestCombinationSubsetDto>) {
+        let universe: BTreeSet<usize> = (1..=6).collect();
+        let subsets = vec![
+            subset(1, &[1, 2, 3, 4], 40),
+            subset(2, &[1, 2, 5], 33),
+            subset(3, &[3, 4, 6], 33),
+            subset(4, &[5], 20),
+            subset(5, &[6], 20),
+        ];
+        (universe, subsets)
+    }
+
+    fn limits() -> SearchLimits {
+        SearchLimits::new(Duration::from_secs(10), usize::MAX)
+    }
+
+    #[test]
+    fn test_exact_beats_greedy_trap() {
+        dotenv::dotenv().ok();
+        let (universe, subsets) = greedy_trap();
+
+        let solution = ExactSolver::new(&universe, &subsets)
+            .solve_k_best(1, &limits())
+            .remove(0);
+        assert_eq!(
+            solution,
+            ExactSolution {
+                package_ids: vec![2, 3],
+                cost: 66,
+                proven_optimal: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_exact_k_best_ranking() {
+        dotenv::dotenv().ok();
+        let (universe, subsets) = greedy_trap();
+
+        let solutions = ExactSolver::new(&universe, &subsets).solve_k_best(5, &limits());
+        let ranking: Vec<(Vec<usize>, usize)> = solutions
+            .iter()
+            .map(|s| (s.package_ids.clone(), s.cost))
+            .collect();
+
+        // {1, 2, 5} and {1, 3, 4} tie at 93 and are ordered by their package IDs.
+        // Covers which only add packages to an earlier cover, like {2, 3, 4}, are not enumerated.
+        assert_eq!(
+            ranking,
+            vec![
+                (vec![2, 3], 66),
+                (vec![1, 4, 5], 80),
+                (vec![1, 2, 5], 93),
+                (vec![1, 3, 4], 93),
+            ]
+        );
+        assert!(solutions.iter().all(|s| s.proven_optimal));
+    }
+
+    #[test]
+    fn test_exact_deadline_returns_incumbent() {
+        dotenv::dotenv().ok();
+        let (universe, subsets) = greedy_trap();
+
+        let solution = ExactSolver::new(&universe, &subsets)
+            .solve_k_best(1, &SearchLimits::new(Duration::ZERO, usize::MAX))
+            .remove(0);
+        assert_eq!(
+            solution,
+            ExactSolution {
+                package_ids: vec![1, 4, 5],
+                cost: 80,
+                proven_optimal: false,
+            },
+            "Should fall back to the greedy incumbent"
+        );
+    }
+
+    #[test]
+    fn test_exact_warm_start_repairs_cover() {
+        dotenv::dotenv().ok();
+        let (universe, subsets) = greedy_trap();
+        let deadline = SearchLimits::new(Duration::ZERO, usize::MAX);
+
+        // Package 4 is redundant next to package 2, and package 9 is no candidate at all
+        for warm_start in [vec![2], vec![2, 3, 4, 9]] {
+            let solution = ExactSolver::new(&universe, &subsets)
+                .warm_start(&warm_start)
+                .solve_k_best(1, &deadline)
+                .remove(0);
+            assert_eq!(
+                solution,
+                ExactSolution {
+                    package_ids: vec![2, 3],
+                    cost: 66,
+                    proven_optimal: false,
+                },
+                "Should start from the repaired warm start instead of the greedy incumbent"
+            );
+        }
+    }
+
+    #[test]
+    fn test_exact_reports_costs() {
+        dotenv::dotenv().ok();
+        let (universe, subsets) = greedy_trap();
+
+        let limits = limits();
+        ExactSolver::new(&universe, &subsets).solve_k_best(1, &limits);
+        assert_eq!(limits.best_cost(), None);
+
+        ExactSolver::new(&universe, &subsets)
+            .report_costs(true)
+            .solve_k_best(1, &limits);
+        assert_eq!(limits.best_cost(), Some(66));
+    }
+
+    #[test]
+    fn test_exact_node_budget_returns_incumbent() {
+        dotenv::dotenv().ok();
+        let (universe, subsets) = greedy_trap();
+        let limits = SearchLimits::new(Duration::from_secs(10), 0);
+
+        let solution = ExactSolver::new(&universe, &subsets)
+            .solve_k_best(1, &limits)
+            .remove(0);
+        assert_eq!(solution.package_ids, vec![1, 4, 5]);
+        assert!(!solution.proven_optimal);
+        assert!(limits.is_exhausted());
+    }
+
+    #[test]
+    fn test_exact_uncoverable_elements() {
+        dotenv::dotenv().ok();
+        let universe: BTreeSet<usize> = (1..=4).collect();
+        let subsets = vec![
+            subset(1, &[1, 2], 10),
+            subset(2, &[2, 3], 10),
+            subset(3, &[1, 3], 15),
+            // Element 4 is never covered
+        ];
+
+        let solution = ExactSolver::new(&universe, &subsets)
+            .solve_k_best(1, &limits())
+            .remove(0);
+        assert_eq!(solution.package_ids, vec![1, 2]);
+        assert_eq!(solution.cost, 20);
+        assert!(solution.proven_optimal);
+    }
+
+    #[test]
+    fn test_exact_empty_input() {
+        dotenv::dotenv().ok();
+        let solution = ExactSolver::new(&BTreeSet::from([1, 2]), &[])
+            .solve_k_best(1, &limits())
+            .remove(0);
+        assert_eq!(
+            solution,
+            ExactSolution {
+                package_ids: vec![],
+                cost: 0,
+                proven_optimal: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_exact_skips_duplicates_and_unbookable_packages() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2]);
+        let mut unbookable = subset(3, &[1, 2], 1);
+        unbookable.monthly_price_cents = None;
+        unbookable.monthly_price_yearly_subscription_in_cents = 1000;
+        let subsets = vec![
+            subset(1, &[1], 5),
+            subset(1, &[1], 5),
+            subset(2, &[2], 5),
+            unbookable,
+        ];
+
+        let solution = ExactSolver::new(&universe, &subsets)
+            .solve_k_best(1, &limits())
+            .remove(0);
+        assert_eq!(solution.package_ids, vec![1, 2]);
+        assert_eq!(solution.cost, 10);
+    }
+
+    #[test]
+    fn test_exact_parallel_matches_sequential() {
+        dotenv::dotenv().ok();
+        let universe: BTreeSet<usize> = (1..=30).collect();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        // Pseudo-random instances with plenty of equally priced alternatives
+        let mut state: u64 = 42;
+        let mut next = |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % bound) as usize
+        };
+
+        for _ in 0..10 {
+            let subsets: Vec<BestCombinationSubsetDto> = (1..=25)
+                .map(|id| {
+                    let game_ids: Vec<usize> = (0..6).map(|_| next(30) + 1).collect();
+                    subset(id, &game_ids, (next(4) + 1) * 5)
+                })
+                .collect();
+
+            let sequential = ExactSolver::new(&universe, &subsets).solve_k_best(5, &limits());
+            let parallel = pool.install(|| {
+                ExactSolver::new(&universe, &subsets)
+                    .parallel(true)
+                    .solve_k_best(5, &limits())
+            });
+            assert_eq!(parallel, sequential);
+        }
+    }
+
+    #[test]
+    fn test_exact_k_best_returns_irredundant_covers() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2]);
+        // The free package 1 ties every cover it is added to, and {1, 2} comes before {2}
+        let subsets = vec![
+            subset(1, &[1], 0),
+            subset(2, &[1, 2], 10),
+            subset(3, &[2], 10),
+            subset(4, &[1, 2], 20),
+        ];
+
+        let solutions = ExactSolver::new(&universe, &subsets).solve_k_best(3, &limits());
+        let ranking: Vec<(Vec<usize>, usize)> = solutions
+            .iter()
+            .map(|s| (s.package_ids.clone(), s.cost))
+            .collect();
+
+        assert_eq!(
+            ranking,
+            vec![(vec![2], 10), (vec![1, 3], 10), (vec![4], 20)]
+        );
+    }
+}
@@ -1,8 +1,13 @@
-use std::collections::{BTreeSet, HashMap};
-
-use libs::models::dtos::{
-    BestCombinationDto, BestCombinationElementDto, BestCombinationPackageDto,
-    BestCombinationSubsetDto,
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use libs::models::{
+    dtos::{
+        BestCombinationDto, BestCombinationElementDto, BestCombinationPackageDto,
+        BestCombinationSubsetDto, GameCoverageDto, PackageExplanationDto, PackageReplacementDto,
+        UncoveredGameDto,
+    },
+    fetch_types::UncoveredReason,
+    schemas::GameSchema,
 };
 
 /// Computes a three-stage coverage value (0, 1, 2) from a slice of u8 values,
@@ -96,6 +101,7 @@ fn build_coverage_map(elements: &BTreeSet<BestCombinationElementDto>) -> HashMap
 /// - A `BestCombinationDto` struct containing:
 ///   - A list of `BestCombinationPackageDto` for each chosen package.
 ///   - The combined monthly price, monthly price for yearly subs.
+///   - The combined price of the packages under their billing options, if all can be booked so.
 ///   - The overall coverage as a percentage (`combined_coverage`).
 pub fn map_to_best_combination_dto(
     current_cover: &[usize],
@@ -123,6 +129,9 @@ pub fn map_to_best_combination_dto(
                 monthly_price_cents: subset.monthly_price_cents,
                 monthly_price_yearly_subscription_in_cents: subset
                     .monthly_price_yearly_subscription_in_cents,
+                billing: subset.billing,
+                explanation: None,
+                owned: false,
             });
             combined_monthly_price_cents += subset.monthly_price_cents.unwrap_or(0);
             combined_monthly_price_yearly_subscription_in_cents +=
@@ -136,22 +145,233 @@ pub fn map_to_best_combination_dto(
         }
     }
 
-    let combined_coverage = (covered.len() as f64 / universe.len() as f64 * 100.0).round() as u8;
+    let combined_coverage = coverage_percentage(covered.len(), universe.len());
 
     packages.sort_by(|package1, package2| package1.id.cmp(&package2.id));
 
+    let combined_billed_price_cents = packages
+        .iter()
+        .map(BestCombinationPackageDto::billed_price_cents)
+        .sum();
+
     BestCombinationDto {
         packages,
         combined_monthly_price_cents,
         combined_monthly_price_yearly_subscription_in_cents,
         combined_coverage,
         index,
+        combined_billed_price_cents,
+        proven_optimal: false,
+        timed_out: false,
+        schedule: None,
+        uncovered_games: Vec::new(),
+        removed_package_ids: Vec::new(),
+        saved_monthly_price_cents: 0,
+        games: Vec::new(),
+    }
+}
+
+/// Computes the share of `covered` games among `total` games as a rounded percentage.
+fn coverage_percentage(covered: usize, total: usize) -> u8 {
+    (covered as f64 / total as f64 * 100.0).round() as u8
+}
+
+/// Adds an explanation to every package of a final combination.
+///
+/// For each package, this function:
+/// 1. Collects the games of the `universe` which no other package of the combination covers.
+/// 2. Computes how many percentage points the combined coverage would lose without the package.
+/// 3. Finds the cheapest bookable package outside of the combination which covers all of those
+///    games, breaking ties by the lowest package ID.
+///
+/// # Parameters
+/// - `combination`: The combination to explain, whose packages receive their `explanation`.
+/// - `subsets`: A slice of `BestCombinationSubsetDto` describing each streaming package and
+///   its set of elements (coverage entries).
+/// - `universe`: A `BTreeSet` of all possible `game_id` values, used for computing overall coverage.
+pub fn explain_packages(
+    combination: &mut BestCombinationDto,
+    subsets: &[BestCombinationSubsetDto],
+    universe: &BTreeSet<usize>,
+) {
+    let chosen_ids: BTreeSet<usize> = combination.packages.iter().map(|p| p.id).collect();
+    let games_of = |id: usize| -> BTreeSet<usize> {
+        subsets
+            .iter()
+            .find(|s| s.streaming_package_id == id)
+            .map(|s| s.element_ids().intersection(universe).copied().collect())
+            .unwrap_or_default()
+    };
+
+    let mut providers: BTreeMap<usize, usize> = BTreeMap::new();
+    for &id in &chosen_ids {
+        for game_id in games_of(id) {
+            *providers.entry(game_id).or_default() += 1;
+        }
+    }
+    let coverage = coverage_percentage(providers.len(), universe.len());
+
+    for package in combination.packages.iter_mut() {
+        let unique_game_ids: Vec<usize> = games_of(package.id)
+            .into_iter()
+            .filter(|game_id| providers.get(game_id) == Some(&1))
+            .collect();
+
+        let coverage_without =
+            coverage_percentage(providers.len() - unique_game_ids.len(), universe.len());
+
+        let cheapest_replacement = if unique_game_ids.is_empty() {
+            None
+        } else {
+            subsets
+                .iter()
+                .filter(|s| !chosen_ids.contains(&s.streaming_package_id))
+                .filter(|s| {
+                    let games = s.element_ids();
+                    unique_game_ids
+                        .iter()
+                        .all(|game_id| games.contains(game_id))
+                })
+                .filter_map(|s| s.billed_price_cents().map(|price| (s, price)))
+                .min_by_key(|(s, price)| (*price, s.streaming_package_id))
+                .map(|(s, price)| PackageReplacementDto {
+                    id: s.streaming_package_id,
+                    name: s.name.clone(),
+                    billed_price_cents: price,
+                })
+        };
+
+        package.explanation = Some(PackageExplanationDto {
+            unique_game_ids,
+            coverage_loss: coverage.saturating_sub(coverage_without),
+            cheapest_replacement,
+        });
+    }
+}
+
+/// Lists the requested games a final combination doesn't cover.
+///
+/// Games which no package offers at all are reported as [`UncoveredReason::NotOffered`], games
+/// which only packages ruled out by the options of the request offer as
+/// [`UncoveredReason::ExcludedByOptions`], and all others as
+/// [`UncoveredReason::NotInCombination`].
+///
+/// # Parameters
+/// - `combination`: The combination to check, which receives its `uncovered_games`.
+/// - `subsets`: A slice of `BestCombinationSubsetDto` describing each streaming package and
+///   its set of elements (coverage entries).
+/// - `universe`: A `BTreeSet` of all possible `game_id` values.
+/// - `games`: The requested games, providing the teams, tournament and kickoff of each game.
+/// - `offered_game_ids`: The games any package offers, before the options of the request
+///   filtered the subsets.
+pub fn list_uncovered_games(
+    combination: &mut BestCombinationDto,
+    subsets: &[BestCombinationSubsetDto],
+    universe: &BTreeSet<usize>,
+    games: &[GameSchema],
+    offered_game_ids: &BTreeSet<usize>,
+) {
+    let chosen_ids: BTreeSet<usize> = combination.packages.iter().map(|p| p.id).collect();
+    let mut bookable: BTreeSet<usize> = BTreeSet::new();
+    let mut covered: BTreeSet<usize> = BTreeSet::new();
+
+    for subset in subsets {
+        if subset.billed_price_cents().is_some() {
+            bookable.extend(subset.element_ids());
+        }
+        if chosen_ids.contains(&subset.streaming_package_id) {
+            covered.extend(subset.element_ids());
+        }
+    }
+
+    let mut uncovered_games: Vec<UncoveredGameDto> = games
+        .iter()
+        .filter(|g| universe.contains(&(g.game_id as usize)))
+        .filter(|g| !covered.contains(&(g.game_id as usize)))
+        .map(|g| {
+            let game_id = g.game_id as usize;
+            let reason = if bookable.contains(&game_id) {
+                UncoveredReason::NotInCombination
+            } else if offered_game_ids.contains(&game_id) {
+                UncoveredReason::ExcludedByOptions
+            } else {
+                UncoveredReason::NotOffered
+            };
+            UncoveredGameDto::new(g, reason)
+        })
+        .collect();
+    uncovered_games.sort_by_key(|g| g.game_id);
+    uncovered_games.dedup_by_key(|g| g.game_id);
+
+    combination.uncovered_games = uncovered_games;
+}
+
+/// Builds the per-game coverage of a final combination, ordered by kickoff and game ID.
+///
+/// Unlike [`build_coverage_map`], this keeps track of every single game: which packages of the
+/// combination cover it, and whether any of them streams it live or offers its highlights.
+///
+/// # Parameters
+/// - `combination`: The combination to map, which receives its `games`.
+/// - `subsets`: A slice of `BestCombinationSubsetDto` describing each streaming package and
+///   its set of elements (coverage entries).
+/// - `universe`: A `BTreeSet` of all possible `game_id` values.
+/// - `games`: The requested games, providing the teams, tournament and kickoff of each game.
+pub fn build_game_coverage(
+    combination: &mut BestCombinationDto,
+    subsets: &[BestCombinationSubsetDto],
+    universe: &BTreeSet<usize>,
+    games: &[GameSchema],
+) {
+    let chosen_ids: BTreeSet<usize> = combination.packages.iter().map(|p| p.id).collect();
+    let mut offers: BTreeMap<usize, Vec<(usize, &BestCombinationElementDto)>> = BTreeMap::new();
+    let mut processed_ids: BTreeSet<usize> = BTreeSet::new();
+
+    for subset in subsets {
+        let id = subset.streaming_package_id;
+        if chosen_ids.contains(&id) && processed_ids.insert(id) {
+            for element in &subset.elements {
+                offers
+                    .entry(element.game_id)
+                    .or_default()
+                    .push((id, element));
+            }
+        }
     }
+
+    let mut coverage: Vec<GameCoverageDto> = games
+        .iter()
+        .filter(|g| universe.contains(&(g.game_id as usize)))
+        .map(|g| {
+            let game_offers = offers
+                .get(&(g.game_id as usize))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let mut package_ids: Vec<usize> = game_offers.iter().map(|(id, _)| *id).collect();
+            package_ids.sort();
+
+            GameCoverageDto {
+                game_id: g.game_id as usize,
+                team_home: g.team_home.clone(),
+                team_away: g.team_away.clone(),
+                tournament_name: g.tournament_name.clone(),
+                starts_at: g.starts_at.clone(),
+                package_ids,
+                live: game_offers.iter().any(|(_, e)| e.live == 1),
+                highlights: game_offers.iter().any(|(_, e)| e.highlights == 1),
+            }
+        })
+        .collect();
+    coverage.sort_by(|a, b| (&a.starts_at, a.game_id).cmp(&(&b.starts_at, b.game_id)));
+    coverage.dedup_by_key(|g| g.game_id);
+
+    combination.games = coverage;
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use libs::models::fetch_types::BillingOption;
 
     #[test]
     fn test_three_stage_coverage_computation() {
@@ -215,6 +435,14 @@ mod tests {
             combined_monthly_price_yearly_subscription_in_cents: 20,
             combined_coverage: 67,
             index: 0,
+            combined_billed_price_cents: None,
+            proven_optimal: false,
+            timed_out: false,
+            schedule: None,
+            uncovered_games: Vec::new(),
+            removed_package_ids: Vec::new(),
+            saved_monthly_price_cents: 0,
+            games: Vec::new(),
         };
 
         assert_eq!(result, expected);
@@ -256,6 +484,14 @@ mod tests {
             combined_monthly_price_yearly_subscription_in_cents: 10,
             combined_coverage: 100,
             index: 0,
+            combined_billed_price_cents: Some(10),
+            proven_optimal: false,
+            timed_out: false,
+            schedule: None,
+            uncovered_games: Vec::new(),
+            removed_package_ids: Vec::new(),
+            saved_monthly_price_cents: 0,
+            games: Vec::new(),
         };
 
         assert_eq!(
@@ -263,4 +499,230 @@ mod tests {
             "Mapper should handle duplicate package IDs correctly"
         );
     }
+
+    #[test]
+    fn test_mapper_sums_billed_prices() {
+        let mut subsets = vec![
+            BestCombinationSubsetDto::new(
+                1,
+                "S1",
+                BTreeSet::from([BestCombinationElementDto::new(1, "A", 1, 1)]),
+                Some(10),
+                8,
+            ),
+            BestCombinationSubsetDto::new(
+                2,
+                "S2",
+                BTreeSet::from([BestCombinationElementDto::new(2, "A", 1, 1)]),
+                Some(5),
+                7,
+            ),
+        ];
+        subsets[0].billing = BillingOption::Yearly;
+        let universe = BTreeSet::from([1, 2]);
+
+        let result = map_to_best_combination_dto(&[1, 2], &subsets, &universe, 0);
+
+        assert_eq!(result.combined_monthly_price_cents, 15);
+        assert_eq!(
+            result.combined_monthly_price_yearly_subscription_in_cents,
+            15
+        );
+        assert_eq!(result.combined_billed_price_cents, Some(13));
+    }
+
+    #[test]
+    fn test_explain_packages() {
+        let subsets = vec![
+            BestCombinationSubsetDto::new(
+                1,
+                "S1",
+                BTreeSet::from([
+                    BestCombinationElementDto::new(1, "A", 1, 1),
+                    BestCombinationElementDto::new(2, "A", 1, 1),
+                ]),
+                Some(10),
+                10,
+            ),
+            BestCombinationSubsetDto::new(
+                2,
+                "S2",
+                BTreeSet::from([
+                    BestCombinationElementDto::new(2, "A", 1, 1),
+                    BestCombinationElementDto::new(3, "B", 1, 1),
+                ]),
+                Some(10),
+                10,
+            ),
+            BestCombinationSubsetDto::new(
+                3,
+                "S3",
+                BTreeSet::from([
+                    BestCombinationElementDto::new(1, "A", 1, 1),
+                    BestCombinationElementDto::new(5, "B", 1, 1),
+                ]),
+                Some(30),
+                30,
+            ),
+            BestCombinationSubsetDto::new(
+                4,
+                "S4",
+                BTreeSet::from([BestCombinationElementDto::new(1, "A", 1, 1)]),
+                Some(20),
+                20,
+            ),
+            BestCombinationSubsetDto::new(
+                5,
+                "S5",
+                BTreeSet::from([BestCombinationElementDto::new(1, "A", 1, 1)]),
+                None,
+                5,
+            ),
+        ];
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+
+        let mut combination = map_to_best_combination_dto(&[1, 2], &subsets, &universe, 0);
+        explain_packages(&mut combination, &subsets, &universe);
+
+        // S5 can't be booked, so S4 is the cheapest package covering game 1 besides S1
+        assert_eq!(
+            combination.packages[0].explanation,
+            Some(PackageExplanationDto {
+                unique_game_ids: vec![1],
+                coverage_loss: 25,
+                cheapest_replacement: Some(PackageReplacementDto {
+                    id: 4,
+                    name: "S4".to_string(),
+                    billed_price_cents: 20,
+                }),
+            })
+        );
+        assert_eq!(
+            combination.packages[1].explanation,
+            Some(PackageExplanationDto {
+                unique_game_ids: vec![3],
+                coverage_loss: 25,
+                cheapest_replacement: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_list_uncovered_games() {
+        let game = |game_id: u32| GameSchema {
+            id: mongodb::bson::oid::ObjectId::new(),
+            game_id,
+            team_home: "TEAM A".to_string(),
+            team_away: "TEAM B".to_string(),
+            starts_at: "2024-06-14 19:00:00".to_string(),
+            tournament_name: "CUP".to_string(),
+        };
+        let subsets = vec![
+            BestCombinationSubsetDto::new(
+                1,
+                "S1",
+                BTreeSet::from([BestCombinationElementDto::new(1, "CUP", 1, 1)]),
+                Some(10),
+                10,
+            ),
+            BestCombinationSubsetDto::new(
+                2,
+                "S2",
+                BTreeSet::from([BestCombinationElementDto::new(2, "CUP", 1, 1)]),
+                Some(10),
+                10,
+            ),
+            // Billed monthly by default, so it can't be booked
+            BestCombinationSubsetDto::new(
+                3,
+                "S3",
+                BTreeSet::from([BestCombinationElementDto::new(4, "CUP", 1, 1)]),
+                None,
+                10,
+            ),
+        ];
+        let universe = BTreeSet::from([1, 2, 3, 4, 6]);
+        let games = vec![game(4), game(3), game(2), game(1), game(5), game(6)];
+        // Game 6 is only offered by a package the options of the request filtered out
+        let offered_game_ids = BTreeSet::from([1, 2, 4, 6]);
+
+        let mut combination = map_to_best_combination_dto(&[1], &subsets, &universe, 0);
+        list_uncovered_games(
+            &mut combination,
+            &subsets,
+            &universe,
+            &games,
+            &offered_game_ids,
+        );
+
+        let uncovered: Vec<(usize, UncoveredReason)> = combination
+            .uncovered_games
+            .iter()
+            .map(|g| (g.game_id, g.reason))
+            .collect();
+        assert_eq!(
+            uncovered,
+            vec![
+                (2, UncoveredReason::NotInCombination),
+                (3, UncoveredReason::NotOffered),
+                (4, UncoveredReason::ExcludedByOptions),
+                (6, UncoveredReason::ExcludedByOptions),
+            ]
+        );
+        assert_eq!(combination.uncovered_games[0].team_home, "TEAM A");
+    }
+
+    #[test]
+    fn test_build_game_coverage() {
+        let game = |game_id: u32, starts_at: &str| GameSchema {
+            id: mongodb::bson::oid::ObjectId::new(),
+            game_id,
+            team_home: "TEAM A".to_string(),
+            team_away: "TEAM B".to_string(),
+            starts_at: starts_at.to_string(),
+            tournament_name: "CUP".to_string(),
+        };
+        let subsets = vec![
+            BestCombinationSubsetDto::new(
+                1,
+                "S1",
+                BTreeSet::from([
+                    BestCombinationElementDto::new(1, "CUP", 0, 1),
+                    BestCombinationElementDto::new(2, "CUP", 1, 1),
+                ]),
+                Some(10),
+                10,
+            ),
+            BestCombinationSubsetDto::new(
+                2,
+                "S2",
+                BTreeSet::from([BestCombinationElementDto::new(1, "CUP", 1, 0)]),
+                Some(10),
+                10,
+            ),
+        ];
+        let universe = BTreeSet::from([1, 2, 3]);
+        let games = vec![
+            game(1, "2024-06-15 19:00:00"),
+            game(2, "2024-06-14 19:00:00"),
+            game(3, "2024-06-16 19:00:00"),
+        ];
+
+        let mut combination = map_to_best_combination_dto(&[1, 2], &subsets, &universe, 0);
+        build_game_coverage(&mut combination, &subsets, &universe, &games);
+
+        let coverage: Vec<(usize, Vec<usize>, bool, bool)> = combination
+            .games
+            .iter()
+            .map(|g| (g.game_id, g.package_ids.clone(), g.live, g.highlights))
+            .collect();
+        assert_eq!(
+            coverage,
+            vec![
+                (2, vec![1], true, true),
+                (1, vec![1, 2], true, true),
+                (3, vec![], false, false),
+            ]
+        );
+    }
 }
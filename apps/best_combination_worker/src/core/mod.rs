@@ -1,5 +1,16 @@
+mod bitset;
+mod budget;
+mod exact;
 mod mapper;
+mod pareto;
 mod processor;
+mod progress;
+mod reduction;
+mod redundancy;
+mod schedule;
 mod service;
+mod solvers;
+mod weights;
 
 pub use processor::Processor;
+pub use solvers::solver_registry;
@@ -0,0 +1,180 @@
+use std::collections::BTreeSet;
+
+use libs::{models::dtos::BestCombinationSubsetDto, solver::SearchLimits};
+
+use super::{
+    budget::{BudgetSolution, BudgetSolver},
+    weights::GameWeights,
+};
+
+/// Traces the Pareto frontier of combined price versus covered weight.
+///
+/// # Overview
+///
+/// A combination is Pareto-optimal if no other combination covers as much weight for less, or more
+/// weight for the same price. The frontier is traced with the epsilon-constraint method: starting
+/// with the full budget, the [`BudgetSolver`] finds the cheapest combination covering the most
+/// weight. The budget is then lowered to one cent below the price of that combination, which
+/// forces the next search to give up some coverage, until nothing can be covered anymore.
+///
+/// Each search yields the next point of the frontier, so there are at most as many searches as
+/// distinct covered weights. All searches share the search `limits`. Once they are exhausted, the
+/// frontier found so far is returned, and its cheapest point is not proven optimal.
+///
+/// # Arguments
+///
+/// * `universe` - The game IDs whose coverage is traded off against the price.
+/// * `subsets` - The candidate streaming packages.
+/// * `budget` - The maximum combined monthly price in cents of the frontier's most expensive point.
+/// * `max_packages` - The maximum number of packages per combination.
+/// * `forced_package_ids` - The IDs of the packages every combination has to contain.
+/// * `weights` - The weights and must-cover constraints of the games.
+/// * `limits` - The search limits shared by all searches.
+///
+/// # Returns
+///
+/// The combinations of the frontier in ascending order of their price and coverage.
+///
+pub fn pareto_frontier(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    budget: usize,
+    max_packages: usize,
+    forced_package_ids: &[usize],
+    weights: &GameWeights,
+    limits: &SearchLimits,
+) -> Vec<BudgetSolution> {
+    let mut frontier: Vec<BudgetSolution> = Vec::new();
+    let mut budget = Some(budget);
+
+    while let Some(current) = budget {
+        let Some(solution) =
+            BudgetSolver::new(universe, subsets, current, weights, forced_package_ids)
+                .max_packages(max_packages)
+                .solve(1, limits)
+                .into_iter()
+                .next()
+        else {
+            break;
+        };
+
+        // A combination that costs nothing can't be undercut by a cheaper one
+        budget = solution.cost.checked_sub(1);
+        frontier.push(solution);
+
+        if limits.is_exhausted() {
+            break;
+        }
+    }
+
+    frontier.reverse();
+    frontier
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use libs::models::dtos::BestCombinationElementDto;
+
+    fn subset(id: usize, game_ids: &[usize], cost: usize) -> BestCombinationSubsetDto {
+        BestCombinationSubsetDto::new(
+            id,
+            &format!("S{}", id),
+            game_ids
+                .iter()
+                .map(|&game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                .collect(),
+            Some(cost),
+            cost,
+        )
+    }
+
+    fn limits() -> SearchLimits {
+        SearchLimits::new(Duration::from_secs(10), usize::MAX)
+    }
+
+    fn points(frontier: &[BudgetSolution]) -> Vec<(Vec<usize>, usize, usize)> {
+        frontier
+            .iter()
+            .map(|s| (s.package_ids.clone(), s.cost, s.covered_weight))
+            .collect()
+    }
+
+    #[test]
+    fn test_pareto_frontier() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+        let subsets = vec![
+            subset(1, &[1, 2, 3, 4], 60),
+            subset(2, &[1, 2, 3], 25),
+            subset(3, &[1], 5),
+            subset(4, &[4], 40),
+            subset(5, &[2], 30),
+        ];
+
+        let frontier = pareto_frontier(
+            &universe,
+            &subsets,
+            usize::MAX,
+            usize::MAX,
+            &[],
+            &GameWeights::default(),
+            &limits(),
+        );
+
+        assert_eq!(
+            points(&frontier),
+            vec![(vec![3], 5, 1), (vec![2], 25, 3), (vec![1], 60, 4)]
+        );
+        assert!(frontier.iter().all(|s| s.proven_optimal));
+    }
+
+    #[test]
+    fn test_pareto_frontier_within_budget() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+        let subsets = vec![subset(1, &[1, 2, 3, 4], 60), subset(2, &[1, 2, 3], 25)];
+
+        let frontier = pareto_frontier(
+            &universe,
+            &subsets,
+            50,
+            usize::MAX,
+            &[],
+            &GameWeights::default(),
+            &limits(),
+        );
+
+        assert_eq!(points(&frontier), vec![(vec![2], 25, 3)]);
+    }
+
+    #[test]
+    fn test_pareto_frontier_with_forced_packages() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+        let subsets = vec![
+            subset(1, &[1, 2, 3, 4], 60),
+            subset(2, &[1, 2, 3], 25),
+            subset(3, &[1], 5),
+            subset(4, &[4], 40),
+            subset(5, &[2], 30),
+        ];
+
+        let frontier = pareto_frontier(
+            &universe,
+            &subsets,
+            usize::MAX,
+            usize::MAX,
+            &[4],
+            &GameWeights::default(),
+            &limits(),
+        );
+
+        assert_eq!(
+            points(&frontier),
+            vec![(vec![4], 40, 1), (vec![3, 4], 45, 2), (vec![2, 4], 65, 4)]
+        );
+    }
+}
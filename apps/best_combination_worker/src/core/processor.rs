@@ -1,21 +1,40 @@
-use std::{collections::BTreeSet, sync::Arc};
+use std::{
+    collections::BTreeSet,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Arc,
+    time::Duration,
+};
 
 use futures::stream::StreamExt;
-use lapin::{message::Delivery, options::BasicAckOptions, Channel, Consumer};
+use lapin::{
+    message::Delivery,
+    options::{BasicAckOptions, BasicNackOptions},
+    Channel, Consumer,
+};
 
 use libs::{
-    caching::{self, CacheValue, CompositeKey, RedisClient},
-    db::dao::StreamingPackageDao,
-    messaging,
-    models::{fetch_types::FetchOptions, payloads::TaskMessagePayload},
+    caching::{self, CacheValue, CompositeKey, RedisClient, WarmStart},
+    db::dao::{GameDao, StreamingPackageDao},
+    messaging, metrics,
+    models::{
+        dtos::{BestCombinationDto, BestCombinationSubsetDto},
+        fetch_types::{CoverageRequirement, PackageFilter, SolverMode},
+        payloads::TaskMessagePayload,
+    },
+    solver::{SearchLimits, SetCoverSolver, SolverRegistry},
 };
 
-use super::service;
+use super::{
+    mapper, progress::ProgressReporter, reduction, redundancy, service, solvers,
+    weights::GameWeights,
+};
 use crate::config::CONFIG;
 
 pub struct Processor {
     redis_client: Arc<RedisClient>,
     package_dao: Arc<StreamingPackageDao>,
+    game_dao: Arc<GameDao>,
+    solvers: Arc<SolverRegistry>,
 }
 
 impl Clone for Processor {
@@ -23,22 +42,32 @@ impl Clone for Processor {
         Processor {
             redis_client: Arc::clone(&self.redis_client),
             package_dao: Arc::clone(&self.package_dao),
+            game_dao: Arc::clone(&self.game_dao),
+            solvers: Arc::clone(&self.solvers),
         }
     }
 }
 
 impl Processor {
-    pub fn new(redis_client: Arc<RedisClient>, package_dao: Arc<StreamingPackageDao>) -> Self {
+    pub fn new(
+        redis_client: Arc<RedisClient>,
+        package_dao: Arc<StreamingPackageDao>,
+        game_dao: Arc<GameDao>,
+    ) -> Self {
         Processor {
             redis_client,
             package_dao,
+            game_dao,
+            solvers: Arc::new(solvers::solver_registry(CONFIG.solver_threads > 1)),
         }
     }
 
     pub async fn start(&self) -> anyhow::Result<()> {
         let channel = messaging::get_channel(&CONFIG.rabbitmq_url).await?;
-        let consumer =
-            messaging::create_consumer(&channel, &CONFIG.task_queue_name, "rust_processor").await?;
+        // The retry and dead-letter queues have to exist before the first job fails
+        messaging::init_mq(&channel, &CONFIG.task_queue_name).await?;
+        let job_queue = messaging::job_queue_name(&CONFIG.task_queue_name);
+        let consumer = messaging::create_consumer(&channel, &job_queue, "rust_processor").await?;
 
         log::info!("Processor is running an waiting for messages...");
 
@@ -72,31 +101,347 @@ impl Processor {
     }
 
     async fn process_message(&self, channel: &Channel, delivery: &Delivery) -> anyhow::Result<()> {
-        let msg = self.parse_message(&delivery.data)?;
-        let subsets = self
+        // A malformed message fails on every delivery, so it is dead-lettered right away. Without a
+        // key there's no cache entry to fail, so the hash of the payload traces it in the DLQ.
+        let msg = match self.parse_message(&delivery.data) {
+            Ok(msg) => msg,
+            Err(e) => {
+                log::error!(
+                    "Dead-lettering malformed message (payload hash {:016x}, {} bytes): {:?}",
+                    payload_hash(&delivery.data),
+                    delivery.data.len(),
+                    e
+                );
+                return dead_letter(channel, delivery).await;
+            }
+        };
+        let key = CompositeKey::from(msg.clone());
+
+        let result = async {
+            let best_combinations = self.solve(&msg, &key).await?;
+            caching::cache_entry(
+                &self.redis_client,
+                &key,
+                CacheValue::Completed(best_combinations),
+            )
+            .await
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                channel
+                    .basic_ack(delivery.delivery_tag, BasicAckOptions::default())
+                    .await?;
+                log::debug!("Finished processing message");
+                Ok(())
+            }
+            Err(e) => self.handle_failure(channel, delivery, &key, e).await,
+        }
+    }
+
+    /// Handles a job which failed, e.g. because a database couldn't be reached.
+    ///
+    /// # Overview
+    ///
+    /// The failure may be transient, so the job is retried up to the configured number of times
+    /// with bounded exponential backoff: it is published to the retry queue with the backoff delay
+    /// as its expiration, and the original delivery is acked. Meanwhile, the job is reported as
+    /// queued again. Once the retries are used up, the job is cached as failed and dead-lettered.
+    ///
+    /// The cache is only updated on a best-effort basis here, as it may be the cause of the failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The channel the job has been delivered on.
+    /// * `delivery` - The delivery of the failed job.
+    /// * `key` - The key of the failed job.
+    /// * `error` - The error the job failed with.
+    ///
+    async fn handle_failure(
+        &self,
+        channel: &Channel,
+        delivery: &Delivery,
+        key: &CompositeKey,
+        error: anyhow::Error,
+    ) -> anyhow::Result<()> {
+        let retries = messaging::retry_count(delivery);
+
+        if retries >= CONFIG.job_retry_limit {
+            log::error!(
+                "Dead-lettering job which failed after {} retries: {:?}",
+                retries,
+                error
+            );
+            self.report(
+                key,
+                CacheValue::Failed {
+                    reason: format!("{:#}", error),
+                },
+            )
+            .await;
+            return dead_letter(channel, delivery).await;
+        }
+
+        let delay = messaging::backoff_delay(
+            retries,
+            Duration::from_millis(CONFIG.job_retry_base_delay_ms),
+            Duration::from_millis(CONFIG.job_retry_max_delay_ms),
+        );
+        log::warn!(
+            "Retrying failed job in {:?} ({}/{}): {:?}",
+            delay,
+            retries + 1,
+            CONFIG.job_retry_limit,
+            error
+        );
+        self.report(key, CacheValue::Queued).await;
+
+        match messaging::retry_job(
+            channel,
+            &CONFIG.task_queue_name,
+            &delivery.data,
+            retries + 1,
+            delay,
+        )
+        .await
+        {
+            Ok(()) => {
+                channel
+                    .basic_ack(delivery.delivery_tag, BasicAckOptions::default())
+                    .await?;
+            }
+            Err(e) => {
+                // Without a delayed retry, the broker redelivers the job right away
+                log::error!("Failed to schedule the retry of a job: {:?}", e);
+                channel
+                    .basic_nack(
+                        delivery.delivery_tag,
+                        BasicNackOptions {
+                            requeue: true,
+                            ..BasicNackOptions::default()
+                        },
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Caches the state of a job, only logging a failure to do so.
+    async fn report(&self, key: &CompositeKey, value: CacheValue<Vec<BestCombinationDto>>) {
+        if let Err(e) = caching::cache_entry(&self.redis_client, key, value).await {
+            log::warn!("Failed to cache the state of a job: {:?}", e);
+        }
+    }
+
+    /// Computes the best combinations of a job, reporting its progress while the solvers search.
+    async fn solve(
+        &self,
+        msg: &TaskMessagePayload,
+        key: &CompositeKey,
+    ) -> anyhow::Result<Vec<BestCombinationDto>> {
+        // The packages are filtered here rather than in the database, as the uncovered games tell
+        // apart the games nobody offers from the ones the options of the request rule out
+        let mut subsets = self
             .package_dao
-            .aggregate_subsets_by_game_ids(&msg.game_ids)
+            .aggregate_subsets_by_game_ids(&msg.game_ids, &PackageFilter::default())
             .await?;
+        let offered_game_ids: BTreeSet<usize> =
+            subsets.iter().flat_map(|s| s.element_ids()).collect();
+        subsets.retain(|s| !msg.package_filter.excludes(s.streaming_package_id, &s.name));
+        let subsets = service::filter_subsets_by_coverage(subsets, msg.coverage);
+        // The live frontier trades off the price against the games which are streamed live only
+        let subsets = if msg.mode == SolverMode::ParetoLiveCoverage {
+            service::filter_subsets_by_coverage(subsets, CoverageRequirement::Live)
+        } else {
+            subsets
+        };
+        let subsets = service::bill_subsets(subsets, msg.pricing);
+        let subsets = service::own_subsets(subsets, &msg.owned_package_ids);
 
-        log::debug!("Performing best combination set cover algorithm...");
+        log::debug!(
+            "Performing best combination set cover algorithm ({:?})...",
+            msg.mode
+        );
         let universe: BTreeSet<usize> = msg.game_ids.iter().copied().collect();
-        let best_combinations = service::get_best_combinations(&universe, &subsets, msg.limit);
 
-        let key = CompositeKey::new(msg.game_ids, FetchOptions::new(msg.limit));
-        caching::cache_entry(
-            &self.redis_client,
-            &key,
-            CacheValue::Data(best_combinations),
-        )
-        .await?;
+        // The covering solvers cover every offered game anyway, so must-covers only constrain the
+        // budgeted search, while the weights also guide the greedy search. Games nobody offers
+        // can't be covered by any combination.
+        let mut weights = GameWeights::new(msg.game_weights.clone(), &msg.must_cover_ids);
+        let unavailable = weights.drop_unavailable_must_covers(&subsets);
+        if !unavailable.is_empty() {
+            log::warn!(
+                "Ignoring must-cover games which aren't offered by any package: {:?}",
+                unavailable
+            );
+        }
 
-        channel
-            .basic_ack(delivery.delivery_tag, BasicAckOptions::default())
-            .await?;
+        let games = self.game_dao.find_games_by_ids(&msg.game_ids).await?;
 
-        log::debug!("Finished processing message");
+        // Owned and required packages are part of every combination, owned ones just come for free
+        let forced_package_ids =
+            service::forced_package_ids(&subsets, &msg.owned_package_ids, &msg.package_filter);
 
-        Ok(())
+        let limits = Arc::new(SearchLimits::new(
+            Duration::from_millis(CONFIG.solver_timeout_ms),
+            CONFIG.solver_node_budget,
+        ));
+        let reporter = ProgressReporter::start(
+            Arc::clone(&self.redis_client),
+            key.clone(),
+            Arc::clone(&limits),
+        );
+        let best_combinations = async {
+            let best_combinations = match (msg.max_monthly_price_cents, msg.max_packages, msg.mode)
+            {
+                (
+                    budget,
+                    max_packages,
+                    SolverMode::ParetoCoverage | SolverMode::ParetoLiveCoverage,
+                ) => service::get_pareto_combinations(
+                    &universe,
+                    &subsets,
+                    budget.unwrap_or(usize::MAX),
+                    max_packages,
+                    &forced_package_ids,
+                    &weights,
+                    &limits,
+                ),
+                (_, _, SolverMode::Scheduled) => service::get_scheduled_combinations(
+                    &universe,
+                    &subsets,
+                    &games,
+                    &forced_package_ids,
+                    &limits,
+                ),
+                // Capping the price or the number of packages turns the search into maximizing the
+                // coverage within the caps, which still yields the cheapest covers if there are any.
+                (budget, max_packages, SolverMode::Greedy | SolverMode::Exact)
+                    if budget.is_some() || max_packages.is_some() =>
+                {
+                    service::get_budgeted_combinations(
+                        &universe,
+                        &subsets,
+                        msg.limit,
+                        budget.unwrap_or(usize::MAX),
+                        max_packages,
+                        &forced_package_ids,
+                        &weights,
+                        &limits,
+                    )
+                }
+                (_, _, SolverMode::Greedy | SolverMode::Exact) => {
+                    let solver = self.select_solver(msg)?;
+                    // The cover of a similar request which has been solved before is a head start
+                    let warm_start = if solver.supports_warm_start() {
+                        self.find_warm_start(key).await
+                    } else {
+                        None
+                    };
+                    let combinations = solve_covering(
+                        solver,
+                        msg,
+                        &universe,
+                        &subsets,
+                        &forced_package_ids,
+                        &limits,
+                        warm_start.as_ref(),
+                    );
+
+                    if let (true, Some(best)) = (solver.supports_warm_start(), combinations.first())
+                    {
+                        let package_ids = best.packages.iter().map(|p| p.id).collect();
+                        if let Err(e) =
+                            caching::record_warm_start(&self.redis_client, key, package_ids).await
+                        {
+                            log::warn!("Failed to record the cover for warm starts: {:?}", e);
+                        }
+                    }
+                    combinations
+                }
+            };
+            anyhow::Ok(best_combinations)
+        }
+        .await;
+        reporter.stop().await;
+        let best_combinations = best_combinations?;
+
+        if limits.is_exhausted() {
+            log::warn!(
+                "Search limits exhausted after {} nodes, returning the best combinations found so far",
+                limits.explored_nodes()
+            );
+        }
+
+        // A scheduled combination holds its packages in different months, so a package may look
+        // redundant for the whole season while being the cheapest option for its month.
+        let mut best_combinations = if msg.mode == SolverMode::Scheduled {
+            best_combinations
+        } else {
+            let (best_combinations, report) = redundancy::strip_redundant_packages(
+                best_combinations,
+                &subsets,
+                &universe,
+                &forced_package_ids,
+            );
+            if report.removed_packages > 0 {
+                log::info!(
+                    "Removed {} redundant packages and merged {} duplicate combinations",
+                    report.removed_packages,
+                    report.merged_duplicates
+                );
+            }
+            best_combinations
+        };
+
+        for combination in best_combinations.iter_mut() {
+            mapper::explain_packages(combination, &subsets, &universe);
+            mapper::list_uncovered_games(
+                combination,
+                &subsets,
+                &universe,
+                &games,
+                &offered_game_ids,
+            );
+            if msg.include_game_coverage {
+                mapper::build_game_coverage(combination, &subsets, &universe, &games);
+            }
+            for package in combination.packages.iter_mut() {
+                package.owned = msg.owned_package_ids.contains(&package.id);
+            }
+        }
+
+        Ok(best_combinations)
+    }
+
+    /// Looks up the cover of a similar request to warm-start the search with. A failed lookup only
+    /// costs the head start, so it is logged instead of failing the request.
+    async fn find_warm_start(&self, key: &CompositeKey) -> Option<WarmStart> {
+        caching::find_warm_start(&self.redis_client, key, CONFIG.warm_start_max_distance)
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to look up a warm start: {:?}", e);
+                None
+            })
+    }
+
+    /// Selects the set cover solver for a request. The solver named in the request takes precedence,
+    /// followed by the one of the `Exact` mode and the configured default solver. Unknown solvers in
+    /// a request fall back to the default one.
+    fn select_solver(&self, msg: &TaskMessagePayload) -> anyhow::Result<&dyn SetCoverSolver> {
+        if let Some(name) = &msg.solver {
+            match self.solvers.get(name) {
+                Ok(solver) => return Ok(solver),
+                Err(e) => log::warn!("{}, falling back to the default solver", e),
+            }
+        }
+
+        match msg.mode {
+            SolverMode::Exact => self.solvers.get("exact"),
+            _ => self.solvers.get(&CONFIG.default_solver),
+        }
     }
 
     fn parse_message(&self, data: &[u8]) -> anyhow::Result<TaskMessagePayload> {
@@ -109,3 +454,165 @@ impl Processor {
         todo!("Graceful shutdown");
     }
 }
+
+/// Runs a covering solver on the request, which is reduced first to the extent the solver supports.
+///
+/// The solver is warm-started from the cover of a similar request if it supports it, which is
+/// recorded in the metrics either way.
+fn solve_covering(
+    solver: &dyn SetCoverSolver,
+    msg: &TaskMessagePayload,
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    forced_package_ids: &[usize],
+    limits: &SearchLimits,
+    warm_start: Option<&WarmStart>,
+) -> Vec<BestCombinationDto> {
+    // The greedy searches would pick different packages on a reduced instance
+    let reduced = if solver.supports_reductions() {
+        reduction::reduce(universe, subsets, forced_package_ids, msg.limit == 1)
+    } else {
+        reduction::force(universe, subsets, forced_package_ids)
+    };
+    // The forced packages aren't searched, but are part of every cover found
+    limits.set_base_cost(
+        subsets
+            .iter()
+            .filter(|s| {
+                reduced
+                    .report
+                    .forced_packages
+                    .contains(&s.streaming_package_id)
+            })
+            .filter_map(BestCombinationSubsetDto::billed_price_cents)
+            .sum(),
+    );
+    log::info!(
+        "Reduced the search to {} games and {} packages: {:?}",
+        reduced.universe.len(),
+        reduced.subsets.len(),
+        reduced.report
+    );
+
+    let warm_start = warm_start.filter(|_| solver.supports_warm_start());
+    metrics::SOLVER_RUN_COUNT
+        .with_label_values(&[if warm_start.is_some() { "warm" } else { "cold" }])
+        .inc();
+
+    let combinations = match warm_start {
+        Some(warm_start) => {
+            log::debug!(
+                "Warm-starting the search from the cover of {} games",
+                warm_start.game_ids.len()
+            );
+            solver.solve_warm(
+                &reduced.universe,
+                &reduced.subsets,
+                msg.limit,
+                &msg.game_weights,
+                limits,
+                &warm_start.package_ids,
+            )
+        }
+        None => solver.solve(
+            &reduced.universe,
+            &reduced.subsets,
+            msg.limit,
+            &msg.game_weights,
+            limits,
+        ),
+    };
+    reduced.expand(combinations, subsets, universe)
+}
+
+/// Hashes the raw payload of a message, to match a logged message with its dead-lettered copy.
+fn payload_hash(payload: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    payload.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Rejects a delivery without requeueing it, so the broker routes it to the dead-letter exchange of
+/// its queue.
+async fn dead_letter(channel: &Channel, delivery: &Delivery) -> anyhow::Result<()> {
+    channel
+        .basic_nack(
+            delivery.delivery_tag,
+            BasicNackOptions {
+                requeue: false,
+                ..BasicNackOptions::default()
+            },
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use libs::models::{dtos::BestCombinationElementDto, fetch_types::FetchOptions};
+
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_near_duplicate_request_is_warm_started() {
+        // The greedy search covers the games with S1, S4 and S5 for 80, S2 and S3 cover them for 66
+        let subsets: Vec<BestCombinationSubsetDto> = [
+            (1, vec![1, 2, 3, 4], 40),
+            (2, vec![1, 2, 5], 33),
+            (3, vec![3, 4, 6], 33),
+            (4, vec![5], 20),
+            (5, vec![6], 20),
+        ]
+        .into_iter()
+        .map(|(id, game_ids, cost)| {
+            BestCombinationSubsetDto::new(
+                id,
+                &format!("S{}", id),
+                game_ids
+                    .into_iter()
+                    .map(|game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                    .collect(),
+                Some(cost),
+                cost,
+            )
+        })
+        .collect();
+        let registry = solvers::solver_registry(false);
+        let solver = registry.get(&config::default_solver()).unwrap();
+
+        // The same request without game 6 has been solved with S2 and S3 before
+        let msg =
+            TaskMessagePayload::from(CompositeKey::new((1..=6).collect(), FetchOptions::new(1)));
+        let recorded = WarmStart {
+            game_ids: (1..=5).collect(),
+            package_ids: vec![2, 3],
+        };
+        assert!(solver.supports_warm_start());
+        assert!(recorded.distance(&msg.game_ids) <= config::default_warm_start_max_distance());
+
+        let universe: BTreeSet<usize> = msg.game_ids.iter().copied().collect();
+        let limits = || SearchLimits::new(Duration::from_secs(60), usize::MAX);
+        let package_ids = |results: &[BestCombinationDto]| -> Vec<usize> {
+            results[0].packages.iter().map(|p| p.id).collect()
+        };
+        let warm_runs = || metrics::SOLVER_RUN_COUNT.with_label_values(&["warm"]).get();
+
+        let cold = solve_covering(solver, &msg, &universe, &subsets, &[], &limits(), None);
+        assert_eq!(package_ids(&cold), vec![1, 4, 5]);
+
+        let runs = warm_runs();
+        let warm = solve_covering(
+            solver,
+            &msg,
+            &universe,
+            &subsets,
+            &[],
+            &limits(),
+            Some(&recorded),
+        );
+        assert_eq!(warm_runs(), runs + 1);
+        assert_eq!(package_ids(&warm), vec![2, 3]);
+        assert_eq!(warm[0].combined_monthly_price_cents, 66);
+    }
+}
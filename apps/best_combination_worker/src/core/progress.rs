@@ -0,0 +1,80 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::{sync::oneshot, task::JoinHandle};
+
+use libs::{
+    caching::{self, CacheValue, CompositeKey, RedisClient},
+    models::dtos::BestCombinationDto,
+    solver::SearchLimits,
+};
+
+/// Interval between two progress updates of a running job.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Periodically reports the progress of a running job to the cache.
+///
+/// # Overview
+///
+/// While the solvers search, the reporter writes a `Running` entry with the explored nodes and the
+/// cheapest cost found so far of the shared search `limits` to the cache entry of the job. Clients
+/// polling the request can therefore follow the search. The first update is written right away.
+///
+/// The `Running` entries expire shortly, so the reporter also keeps the entry of the job alive. It
+/// has to be [stopped](ProgressReporter::stop) before the result of the job is cached, so a late
+/// update can't overwrite it.
+pub struct ProgressReporter {
+    stop: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl ProgressReporter {
+    /// Starts reporting the progress of the job of `key` on a separate task.
+    ///
+    /// # Arguments
+    ///
+    /// * `redis_client` - The Redis client of the cache.
+    /// * `key` - The key of the running job.
+    /// * `limits` - The search limits shared by the searches of the job.
+    ///
+    /// # Returns
+    ///
+    /// The reporter, which reports until it is stopped.
+    ///
+    pub fn start(
+        redis_client: Arc<RedisClient>,
+        key: CompositeKey,
+        limits: Arc<SearchLimits>,
+    ) -> Self {
+        let (stop, mut stopped) = oneshot::channel();
+
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PROGRESS_INTERVAL);
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = &mut stopped => break,
+                    _ = interval.tick() => {
+                        let progress = CacheValue::<Vec<BestCombinationDto>>::Running {
+                            explored_nodes: limits.explored_nodes(),
+                            best_cost_so_far: limits.best_cost(),
+                        };
+                        // A missed update only delays the progress, so it doesn't fail the job
+                        if let Err(e) = caching::cache_entry(&redis_client, &key, progress).await {
+                            log::warn!("Failed to report the progress of a job: {:?}", e);
+                        }
+                    }
+                }
+            }
+        });
+
+        ProgressReporter { stop, handle }
+    }
+
+    /// Stops reporting and waits for an update in flight to be written.
+    pub async fn stop(self) {
+        let _ = self.stop.send(());
+        if let Err(e) = self.handle.await {
+            log::warn!("Progress reporter failed: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,523 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use libs::models::dtos::{BestCombinationDto, BestCombinationSubsetDto};
+
+use super::mapper;
+
+/// Diagnostics of a [`reduce`] pass.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReductionReport {
+    /// Packages dropped because another package covers a superset of their remaining games for a
+    /// lower or equal price, or because the forced packages already cover all of their games.
+    pub dominated_packages: Vec<usize>,
+    /// Packages which are owned by the user or are the sole provider of a game, and are therefore
+    /// part of every cover.
+    pub forced_packages: Vec<usize>,
+    /// Number of games merged into another game which is offered by exactly the same packages.
+    pub collapsed_games: usize,
+}
+
+/// A set cover instance reduced by [`reduce`].
+#[derive(Debug, Clone)]
+pub struct Reduction {
+    /// The games left to cover, one representative per group of games offered by the same packages.
+    pub universe: BTreeSet<usize>,
+    /// The packages left to choose from, restricted to the games of the reduced `universe`.
+    pub subsets: Vec<BestCombinationSubsetDto>,
+    pub report: ReductionReport,
+}
+
+/// Applies the classic set cover reductions to shrink the search space before the search starts.
+///
+/// # Overview
+///
+/// Packages the user already owns are forced upfront, and the games they cover are removed from the
+/// universe. The following reductions are repeated until none of them applies anymore:
+///
+/// 1. A package is dominated if another package covers a superset of its games for a lower or equal
+///    price. Any cover containing it stays a cover if it is swapped for the dominating package,
+///    without getting more expensive, so it is dropped. Packages with identical games and prices
+///    don't dominate each other, as they form equally good alternatives.
+///
+///    Swapping a dominated package can turn two distinct covers into the same one, so the runner-up
+///    covers may contain dominated packages. Dominance is therefore only applied if `drop_dominated`
+///    is set, i.e. if just the cheapest cover is searched for. Packages whose games are all covered
+///    by the forced packages are always dropped.
+/// 2. A bookable package which is the only one offering some game is part of every cover. It is
+///    forced, and the games it covers are removed from the universe.
+///
+/// Afterwards, games which are offered by exactly the same packages are collapsed into a single
+/// representative, as covering one of them always covers all of them.
+///
+/// Packages which can't be booked are treated as infinitely expensive, like in the searches.
+///
+/// The reductions keep the cheapest covers, but can change the combinations the greedy searches
+/// pick, so those only search the instance of [`force`].
+///
+/// # Arguments
+///
+/// * `universe` - The game IDs that must be covered.
+/// * `subsets` - The candidate streaming packages.
+/// * `forced_package_ids` - The IDs of the packages every combination has to contain, like the
+///   packages the user already owns or the ones the request requires.
+/// * `drop_dominated` - Whether to drop dominated packages, which is only safe if a single cover is
+///   searched for.
+///
+/// # Returns
+///
+/// The [`Reduction`] to search over. Its combinations have to be passed to [`Reduction::expand`]
+/// to add the forced packages back.
+///
+pub fn reduce(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    forced_package_ids: &[usize],
+    drop_dominated: bool,
+) -> Reduction {
+    let mut report = ReductionReport::default();
+    let (mut remaining, mut candidates) =
+        split_forced(universe, subsets, forced_package_ids, &mut report);
+
+    loop {
+        let dominated: Vec<usize> = (0..candidates.len())
+            .filter(|&i| {
+                let (subset, games) = &candidates[i];
+                games.is_empty()
+                    || drop_dominated
+                        && candidates.iter().any(|(other, other_games)| {
+                            other.streaming_package_id != subset.streaming_package_id
+                                && dominates(other, other_games, subset, games)
+                        })
+            })
+            .collect();
+
+        for &i in dominated.iter().rev() {
+            let (subset, _) = candidates.remove(i);
+            report.dominated_packages.push(subset.streaming_package_id);
+        }
+
+        let forced: BTreeSet<usize> = remaining
+            .iter()
+            .filter_map(|game_id| {
+                let mut providers = candidates
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, games))| games.contains(game_id));
+                match (providers.next(), providers.next()) {
+                    (Some((i, (subset, _))), None) => {
+                        subset.billed_price_cents().is_some().then_some(i)
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+
+        if dominated.is_empty() && forced.is_empty() {
+            break;
+        }
+
+        for &i in forced.iter().rev() {
+            let (subset, games) = candidates.remove(i);
+            report.forced_packages.push(subset.streaming_package_id);
+            remaining.retain(|game_id| !games.contains(game_id));
+        }
+
+        for (_, games) in candidates.iter_mut() {
+            games.retain(|game_id| remaining.contains(game_id));
+        }
+    }
+
+    // Group the remaining games by the packages offering them, represented by the smallest game ID
+    let mut groups: BTreeMap<Vec<usize>, usize> = BTreeMap::new();
+    for &game_id in &remaining {
+        let providers: Vec<usize> = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, games))| games.contains(&game_id))
+            .map(|(i, _)| i)
+            .collect();
+        groups.entry(providers).or_insert(game_id);
+    }
+
+    let representatives: BTreeSet<usize> = groups.into_values().collect();
+    report.collapsed_games = remaining.len() - representatives.len();
+    report.dominated_packages.sort();
+    report.forced_packages.sort();
+
+    let subsets = candidates
+        .into_iter()
+        .map(|(subset, _)| {
+            let mut subset = subset.clone();
+            subset
+                .elements
+                .retain(|e| representatives.contains(&e.game_id));
+            subset
+        })
+        .collect();
+
+    Reduction {
+        universe: representatives,
+        subsets,
+        report,
+    }
+}
+
+/// Only forces the given packages, like [`reduce`] does upfront, and keeps the other packages and
+/// games as they are.
+///
+/// The ratio of the greedy search counts the uncovered games, so the remaining reductions would
+/// change which packages it picks: a collapsed group of games only counts once, and forcing a sole
+/// provider upfront changes the games left for the others. Searching the instance returned here
+/// yields the same combinations as searching the whole instance, apart from the forced packages.
+///
+/// # Arguments
+///
+/// * `universe` - The game IDs that must be covered.
+/// * `subsets` - The candidate streaming packages.
+/// * `forced_package_ids` - The IDs of the packages every combination has to contain.
+///
+/// # Returns
+///
+/// The [`Reduction`] to search over. Its combinations have to be passed to [`Reduction::expand`]
+/// to add the forced packages back.
+///
+pub fn force(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    forced_package_ids: &[usize],
+) -> Reduction {
+    let mut report = ReductionReport::default();
+    let (remaining, candidates) = split_forced(universe, subsets, forced_package_ids, &mut report);
+    report.forced_packages.sort();
+
+    let subsets = candidates
+        .into_iter()
+        .map(|(subset, _)| {
+            let mut subset = subset.clone();
+            subset.elements.retain(|e| remaining.contains(&e.game_id));
+            subset
+        })
+        .collect();
+
+    Reduction {
+        universe: remaining,
+        subsets,
+        report,
+    }
+}
+
+/// Splits the `subsets` into the forced packages, which are recorded in the `report`, and the
+/// candidates with their games of the `universe` the forced packages leave uncovered.
+///
+/// # Returns
+///
+/// The games left to cover and the candidates.
+///
+fn split_forced<'a>(
+    universe: &BTreeSet<usize>,
+    subsets: &'a [BestCombinationSubsetDto],
+    forced_package_ids: &[usize],
+    report: &mut ReductionReport,
+) -> (
+    BTreeSet<usize>,
+    Vec<(&'a BestCombinationSubsetDto, BTreeSet<usize>)>,
+) {
+    let mut remaining = universe.clone();
+    let mut candidates: Vec<(&BestCombinationSubsetDto, BTreeSet<usize>)> = Vec::new();
+
+    for subset in subsets {
+        let games: BTreeSet<usize> = subset
+            .element_ids()
+            .intersection(universe)
+            .copied()
+            .collect();
+        if forced_package_ids.contains(&subset.streaming_package_id) {
+            if !report
+                .forced_packages
+                .contains(&subset.streaming_package_id)
+            {
+                report.forced_packages.push(subset.streaming_package_id);
+            }
+            remaining.retain(|game_id| !games.contains(game_id));
+        } else {
+            candidates.push((subset, games));
+        }
+    }
+    for (_, games) in candidates.iter_mut() {
+        games.retain(|game_id| remaining.contains(game_id));
+    }
+
+    (remaining, candidates)
+}
+
+/// Whether `a` covers all games of `b` for a lower or equal price, and is strictly better in one of
+/// both.
+fn dominates(
+    a: &BestCombinationSubsetDto,
+    a_games: &BTreeSet<usize>,
+    b: &BestCombinationSubsetDto,
+    b_games: &BTreeSet<usize>,
+) -> bool {
+    let a_cost = a.billed_price_cents().unwrap_or(usize::MAX);
+    let b_cost = b.billed_price_cents().unwrap_or(usize::MAX);
+
+    a_cost <= b_cost && b_games.is_subset(a_games) && (a_cost < b_cost || a_games != b_games)
+}
+
+impl Reduction {
+    /// Maps combinations found for the reduced instance back onto the original one, adding the
+    /// forced packages to each of them. The price and coverage are recomputed via
+    /// [`mapper::map_to_best_combination_dto`], while the index and the search diagnostics are kept.
+    pub fn expand(
+        &self,
+        combinations: Vec<BestCombinationDto>,
+        subsets: &[BestCombinationSubsetDto],
+        universe: &BTreeSet<usize>,
+    ) -> Vec<BestCombinationDto> {
+        combinations
+            .into_iter()
+            .map(|combination| {
+                let package_ids: Vec<usize> = combination
+                    .packages
+                    .iter()
+                    .map(|p| p.id)
+                    .chain(self.report.forced_packages.iter().copied())
+                    .collect();
+
+                let mut expanded = mapper::map_to_best_combination_dto(
+                    &package_ids,
+                    subsets,
+                    universe,
+                    combination.index,
+                );
+                expanded.proven_optimal = combination.proven_optimal;
+                expanded.timed_out = combination.timed_out;
+                expanded
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::core::{
+        exact::ExactSolver, service, solvers::solver_registry, weights::GameWeights,
+    };
+    use libs::{models::dtos::BestCombinationElementDto, solver::SearchLimits};
+
+    fn subset(id: usize, game_ids: &[usize], cost: Option<usize>) -> BestCombinationSubsetDto {
+        BestCombinationSubsetDto::new(
+            id,
+            &format!("S{}", id),
+            game_ids
+                .iter()
+                .map(|&game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                .collect(),
+            cost,
+            cost.unwrap_or(0),
+        )
+    }
+
+    #[test]
+    fn test_drop_dominated_packages() {
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+        let subsets = vec![
+            subset(1, &[1, 2], Some(10)),
+            subset(2, &[1], Some(10)),
+            subset(3, &[1, 2], Some(15)),
+            subset(4, &[3, 4], Some(5)),
+            subset(5, &[3, 4], Some(5)),
+            subset(6, &[2, 3], Some(5)),
+            subset(7, &[4, 1], None),
+        ];
+
+        let reduction = reduce(&universe, &subsets, &[], true);
+
+        assert_eq!(reduction.report.dominated_packages, vec![2, 3]);
+        assert!(reduction.report.forced_packages.is_empty());
+        let ids: Vec<usize> = reduction
+            .subsets
+            .iter()
+            .map(|s| s.streaming_package_id)
+            .collect();
+        assert_eq!(ids, vec![1, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_force_sole_providers() {
+        let universe = BTreeSet::from([1, 2, 3, 4, 5]);
+        let subsets = vec![
+            subset(1, &[1, 2], Some(10)),
+            subset(2, &[2, 3], Some(10)),
+            subset(3, &[3, 4], Some(10)),
+            subset(4, &[4, 1], Some(10)),
+            subset(5, &[5], None),
+        ];
+
+        let reduction = reduce(&universe, &subsets, &[], true);
+
+        // Game 5 is only offered by a package which can't be booked, so nothing is forced
+        assert!(reduction.report.forced_packages.is_empty());
+
+        let subsets = vec![
+            subset(1, &[1, 2, 5], Some(10)),
+            subset(2, &[2, 3], Some(10)),
+            subset(3, &[3, 4], Some(10)),
+        ];
+
+        let reduction = reduce(&universe, &subsets, &[], true);
+
+        // S1 is the only one offering games 1 and 5, S3 the only one offering game 4. Together they
+        // cover all games, which leaves S2 without any
+        assert_eq!(reduction.report.forced_packages, vec![1, 3]);
+        assert_eq!(reduction.report.dominated_packages, vec![2]);
+        assert!(reduction.universe.is_empty());
+        assert!(reduction.subsets.is_empty());
+    }
+
+    #[test]
+    fn test_collapse_games_offered_together() {
+        let universe = BTreeSet::from([1, 2, 3, 4, 5, 6]);
+        let subsets = vec![
+            subset(1, &[1, 2, 3, 6], Some(10)),
+            subset(2, &[2, 3, 4, 5], Some(10)),
+            subset(3, &[1, 4, 5, 6], Some(10)),
+        ];
+
+        let reduction = reduce(&universe, &subsets, &[], true);
+
+        assert_eq!(reduction.universe, BTreeSet::from([1, 2, 4]));
+        assert_eq!(reduction.report.collapsed_games, 3);
+        assert_eq!(reduction.subsets[0].element_ids(), BTreeSet::from([1, 2]));
+        assert_eq!(reduction.subsets[1].element_ids(), BTreeSet::from([2, 4]));
+        assert_eq!(reduction.subsets[2].element_ids(), BTreeSet::from([1, 4]));
+    }
+
+    #[test]
+    fn test_expand_adds_forced_packages() {
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+        let subsets = vec![
+            subset(1, &[1, 2], Some(10)),
+            subset(2, &[2, 3], Some(10)),
+            subset(3, &[3, 4], Some(15)),
+            subset(4, &[2, 4], Some(10)),
+        ];
+
+        let reduction = reduce(&universe, &subsets, &[], true);
+        assert_eq!(reduction.report.forced_packages, vec![1]);
+        assert_eq!(reduction.universe, BTreeSet::from([3, 4]));
+
+        let mut combination =
+            mapper::map_to_best_combination_dto(&[3], &reduction.subsets, &reduction.universe, 0);
+        combination.proven_optimal = true;
+
+        let expanded = reduction.expand(vec![combination], &subsets, &universe);
+
+        assert_eq!(
+            expanded,
+            vec![{
+                let mut expected =
+                    mapper::map_to_best_combination_dto(&[1, 3], &subsets, &universe, 0);
+                expected.proven_optimal = true;
+                expected
+            }]
+        );
+        assert_eq!(expanded[0].combined_coverage, 100);
+    }
+
+    #[test]
+    fn test_force_owned_packages() {
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+        let subsets = vec![
+            subset(1, &[1, 2], Some(0)),
+            subset(2, &[2, 3], Some(10)),
+            subset(3, &[3, 4], Some(10)),
+            subset(4, &[1, 4], Some(10)),
+        ];
+
+        let reduction = reduce(&universe, &subsets, &[1], true);
+
+        // Once S1 covers games 1 and 2, S3 dominates S2 and S4, and is forced as sole provider
+        assert_eq!(reduction.report.forced_packages, vec![1, 3]);
+        assert_eq!(reduction.report.dominated_packages, vec![2, 4]);
+        assert!(reduction.universe.is_empty());
+    }
+
+    #[test]
+    fn test_keep_dominated_packages_for_runner_up_covers() {
+        let universe = BTreeSet::from([1, 2]);
+        let subsets = vec![
+            subset(1, &[1, 2], Some(10)),
+            subset(2, &[1], Some(11)),
+            subset(3, &[2], Some(1)),
+        ];
+        let limits = SearchLimits::new(Duration::from_secs(10), usize::MAX);
+
+        // Without S2, S1 is the only one offering game 1, which leaves S3 without any
+        let reduction = reduce(&universe, &subsets, &[], true);
+        assert_eq!(reduction.report.forced_packages, vec![1]);
+        assert_eq!(reduction.report.dominated_packages, vec![2, 3]);
+
+        // The runner-up cover needs S2, although S1 dominates it
+        let reduction = reduce(&universe, &subsets, &[], false);
+        assert!(reduction.report.dominated_packages.is_empty());
+        let covers: Vec<Vec<usize>> = ExactSolver::new(&reduction.universe, &reduction.subsets)
+            .solve_k_best(2, &limits)
+            .into_iter()
+            .map(|solution| solution.package_ids)
+            .collect();
+        assert_eq!(covers, vec![vec![1], vec![2, 3]]);
+    }
+
+    #[test]
+    fn test_force_keeps_greedy_results() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3, 4, 5]);
+        let subsets = vec![
+            subset(1, &[1, 2, 3], Some(9)),
+            subset(2, &[1, 2, 3, 4], Some(14)),
+            subset(3, &[4, 5], Some(8)),
+            subset(4, &[5], Some(3)),
+        ];
+        let registry = solver_registry(false);
+        let recursive = registry.get("recursive").unwrap();
+        assert!(!recursive.supports_reductions());
+        let solve = |reduction: &Reduction, limit| {
+            let limits = SearchLimits::new(Duration::from_secs(10), usize::MAX);
+            let combinations = recursive.solve(
+                &reduction.universe,
+                &reduction.subsets,
+                limit,
+                &BTreeMap::new(),
+                &limits,
+            );
+            reduction.expand(combinations, &subsets, &universe)
+        };
+
+        // Games 1 to 3 collapse into game 1, which makes S1 look like the worst deal
+        let reduced = solve(&reduce(&universe, &subsets, &[], true), 1);
+        let unreduced = solve(&force(&universe, &subsets, &[]), 1);
+        assert_ne!(reduced, unreduced);
+
+        for limit in [1, 3] {
+            let limits = SearchLimits::new(Duration::from_secs(10), usize::MAX);
+            let expected = service::get_best_combinations(
+                &universe,
+                &subsets,
+                limit,
+                &GameWeights::default(),
+                &limits,
+            );
+            assert_eq!(solve(&force(&universe, &subsets, &[]), limit), expected);
+        }
+
+        // Forcing S4 only covers game 5 upfront
+        let forced = force(&universe, &subsets, &[4]);
+        assert_eq!(forced.report.forced_packages, vec![4]);
+        assert_eq!(forced.universe, BTreeSet::from([1, 2, 3, 4]));
+        assert_eq!(forced.subsets.len(), 3);
+    }
+}
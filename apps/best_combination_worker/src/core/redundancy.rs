@@ -0,0 +1,288 @@
+use std::collections::BTreeSet;
+
+use libs::models::dtos::{BestCombinationDto, BestCombinationSubsetDto};
+
+use super::mapper;
+
+/// Diagnostics of a [`strip_redundant_packages`] pass.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RedundancyReport {
+    /// Number of packages removed across all combinations.
+    pub removed_packages: usize,
+    /// Number of combinations dropped because they became duplicates of another one.
+    pub merged_duplicates: usize,
+}
+
+/// Reduces every combination to a minimal (irredundant) cover.
+///
+/// # Overview
+///
+/// The greedy search may pick a package early which is later fully subsumed by the packages chosen
+/// after it. Such a package doesn't add any coverage but still has to be paid for. This pass removes
+/// every package whose games within the `universe` are already covered by the remaining packages
+/// of its combination, starting with the most expensive one. Forced packages, like the ones the user
+/// owns or the request requires, are never removed.
+///
+/// The price and coverage of a reduced combination are recomputed via
+/// [`mapper::map_to_best_combination_dto`], and the removed packages and the billed price they
+/// cost are recorded on it for diagnostics. Combinations which end up identical are merged, keeping
+/// the first occurrence, and the indices are reassigned in order.
+///
+/// # Arguments
+///
+/// * `combinations` - The combinations returned by a solver.
+/// * `subsets` - The candidate streaming packages the combinations were built from.
+/// * `universe` - The game IDs the combinations are supposed to cover.
+/// * `forced_package_ids` - The IDs of the packages every combination has to keep.
+///
+/// # Returns
+///
+/// The reduced combinations and a [`RedundancyReport`] for diagnostics.
+///
+pub fn strip_redundant_packages(
+    combinations: Vec<BestCombinationDto>,
+    subsets: &[BestCombinationSubsetDto],
+    universe: &BTreeSet<usize>,
+    forced_package_ids: &[usize],
+) -> (Vec<BestCombinationDto>, RedundancyReport) {
+    let mut report = RedundancyReport::default();
+    let mut results: Vec<BestCombinationDto> = Vec::with_capacity(combinations.len());
+
+    for combination in combinations {
+        let mut cover: Vec<&BestCombinationSubsetDto> = combination
+            .packages
+            .iter()
+            .filter_map(|p| subsets.iter().find(|s| s.streaming_package_id == p.id))
+            .collect();
+
+        // Try to remove the most expensive packages first
+        cover.sort_by_key(|s| {
+            (
+                std::cmp::Reverse(s.billed_price_cents().unwrap_or(usize::MAX)),
+                s.streaming_package_id,
+            )
+        });
+
+        let mut removed: Vec<&BestCombinationSubsetDto> = Vec::new();
+        let mut i = 0;
+        while i < cover.len() {
+            let covered_by_others: BTreeSet<usize> = cover
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .flat_map(|(_, s)| s.element_ids())
+                .collect();
+
+            let redundant = !forced_package_ids.contains(&cover[i].streaming_package_id)
+                && cover[i]
+                    .element_ids()
+                    .iter()
+                    .filter(|id| universe.contains(id))
+                    .all(|id| covered_by_others.contains(id));
+
+            if redundant {
+                removed.push(cover.remove(i));
+                report.removed_packages += 1;
+            } else {
+                i += 1;
+            }
+        }
+
+        if cover.len() == combination.packages.len() {
+            results.push(combination);
+            continue;
+        }
+
+        let package_ids: Vec<usize> = cover.iter().map(|s| s.streaming_package_id).collect();
+        let mut reduced =
+            mapper::map_to_best_combination_dto(&package_ids, subsets, universe, combination.index);
+        reduced.proven_optimal = combination.proven_optimal;
+        reduced.timed_out = combination.timed_out;
+        reduced.removed_package_ids = removed.iter().map(|s| s.streaming_package_id).collect();
+        reduced.removed_package_ids.sort();
+        reduced.saved_monthly_price_cents =
+            removed.iter().filter_map(|s| s.billed_price_cents()).sum();
+        results.push(reduced);
+    }
+
+    let mut merged: Vec<BestCombinationDto> = Vec::with_capacity(results.len());
+    for combination in results {
+        if merged.iter().any(|r| r.is_duplicate_of(&combination)) {
+            report.merged_duplicates += 1;
+        } else {
+            merged.push(combination);
+        }
+    }
+
+    for (index, combination) in merged.iter_mut().enumerate() {
+        combination.index = index;
+    }
+
+    (merged, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libs::models::dtos::{BestCombinationElementDto, BestCombinationPackageDto};
+
+    fn subset(id: usize, game_ids: &[usize], cost: usize) -> BestCombinationSubsetDto {
+        BestCombinationSubsetDto::new(
+            id,
+            &format!("S{}", id),
+            game_ids
+                .iter()
+                .map(|&game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                .collect(),
+            Some(cost),
+            cost,
+        )
+    }
+
+    fn package(id: usize, cost: usize) -> BestCombinationPackageDto {
+        BestCombinationPackageDto::new(
+            id,
+            &format!("S{}", id),
+            vec![("", (2, 2))],
+            Some(cost),
+            cost,
+        )
+    }
+
+    #[test]
+    fn test_strip_subsumed_package() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+        let subsets = vec![
+            subset(1, &[1, 2], 5),
+            subset(2, &[1, 3], 5),
+            subset(3, &[2, 4], 5),
+        ];
+        let combinations = vec![BestCombinationDto::new(
+            vec![package(1, 5), package(2, 5), package(3, 5)],
+            15,
+            15,
+            100,
+            0,
+        )];
+
+        let (results, report) = strip_redundant_packages(combinations, &subsets, &universe, &[]);
+
+        let mut expected =
+            BestCombinationDto::new(vec![package(2, 5), package(3, 5)], 10, 10, 100, 0);
+        expected.removed_package_ids = vec![1];
+        expected.saved_monthly_price_cents = 5;
+        assert_eq!(results, vec![expected]);
+        assert_eq!(
+            report,
+            RedundancyReport {
+                removed_packages: 1,
+                merged_duplicates: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_strip_most_expensive_first() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2]);
+        let subsets = vec![subset(1, &[1, 2], 5), subset(2, &[1, 2], 20)];
+        let combinations = vec![BestCombinationDto::new(
+            vec![package(1, 5), package(2, 20)],
+            25,
+            25,
+            100,
+            0,
+        )];
+
+        let (results, report) = strip_redundant_packages(combinations, &subsets, &universe, &[]);
+
+        let mut expected = BestCombinationDto::new(vec![package(1, 5)], 5, 5, 100, 0);
+        expected.removed_package_ids = vec![2];
+        expected.saved_monthly_price_cents = 20;
+        assert_eq!(results, vec![expected]);
+        assert_eq!(report.removed_packages, 1);
+    }
+
+    #[test]
+    fn test_merge_duplicates_after_stripping() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2]);
+        let subsets = vec![
+            subset(1, &[1], 5),
+            subset(2, &[2], 5),
+            subset(3, &[1], 10),
+            subset(4, &[2, 5], 10),
+        ];
+        let combinations = vec![
+            BestCombinationDto::new(vec![package(1, 5), package(2, 5)], 10, 10, 100, 0),
+            BestCombinationDto::new(
+                vec![package(1, 5), package(2, 5), package(3, 10)],
+                20,
+                20,
+                100,
+                1,
+            ),
+            BestCombinationDto::new(vec![package(1, 5), package(4, 10)], 15, 15, 100, 2),
+        ];
+
+        let (results, report) = strip_redundant_packages(combinations, &subsets, &universe, &[]);
+
+        assert_eq!(
+            results,
+            vec![
+                BestCombinationDto::new(vec![package(1, 5), package(2, 5)], 10, 10, 100, 0),
+                BestCombinationDto::new(vec![package(1, 5), package(4, 10)], 15, 15, 100, 1),
+            ]
+        );
+        assert_eq!(
+            report,
+            RedundancyReport {
+                removed_packages: 1,
+                merged_duplicates: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_keep_irredundant_combinations() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3]);
+        let subsets = vec![subset(1, &[1], 5), subset(2, &[2], 5)];
+        let combinations = vec![BestCombinationDto::new(
+            vec![package(1, 5), package(2, 5)],
+            10,
+            10,
+            67,
+            0,
+        )];
+
+        let (results, report) =
+            strip_redundant_packages(combinations.clone(), &subsets, &universe, &[]);
+
+        assert_eq!(results, combinations);
+        assert_eq!(report, RedundancyReport::default());
+    }
+
+    #[test]
+    fn test_keep_forced_packages() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2]);
+        let subsets = vec![subset(1, &[1, 2], 5), subset(2, &[1, 2], 20)];
+        let combinations = vec![BestCombinationDto::new(
+            vec![package(1, 5), package(2, 20)],
+            25,
+            25,
+            100,
+            0,
+        )];
+
+        let (results, report) = strip_redundant_packages(combinations, &subsets, &universe, &[2]);
+
+        let mut expected = BestCombinationDto::new(vec![package(2, 20)], 20, 20, 100, 0);
+        expected.removed_package_ids = vec![1];
+        expected.saved_monthly_price_cents = 5;
+        assert_eq!(results, vec![expected]);
+        assert_eq!(report.removed_packages, 1);
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use libs::{
+    models::{
+        dtos::{BestCombinationSubsetDto, MonthlyPlanDto, SeasonScheduleDto},
+        schemas::GameSchema,
+    },
+    solver::SearchLimits,
+};
+
+use super::exact::{ExactSolution, ExactSolver};
+
+/// Number of months a yearly subscription has to be paid for.
+const MONTHS_PER_YEAR: usize = 12;
+
+/// A month-by-month subscription plan together with the diagnostics of its computation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeasonPlan {
+    pub schedule: SeasonScheduleDto,
+    /// All packages held in at least one month of the plan.
+    pub package_ids: BTreeSet<usize>,
+    pub proven_optimal: bool,
+}
+
+/// Returns the calendar month (`YYYY-MM`) of a `starts_at` timestamp.
+fn month_of(starts_at: &str) -> Option<&str> {
+    starts_at.get(..7)
+}
+
+/// Returns the number of calendar months from the first to the last month, both inclusive.
+fn month_span(first: &str, last: &str) -> Option<usize> {
+    let index = |month: &str| -> Option<usize> {
+        let (year, month) = month.split_once('-')?;
+        Some(year.parse::<usize>().ok()? * MONTHS_PER_YEAR + month.parse::<usize>().ok()?)
+    };
+
+    Some(index(last)? - index(first)? + 1)
+}
+
+/// Returns the cheapest cover of `games` with the packages priced by `price`, or `None` if there is
+/// none. Without any games left to cover, the empty cover is the cheapest one.
+fn cheapest_cover(
+    games: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    price: impl Fn(&BestCombinationSubsetDto) -> Option<usize>,
+    limits: &SearchLimits,
+) -> Option<ExactSolution> {
+    if games.is_empty() {
+        return Some(ExactSolution {
+            package_ids: Vec::new(),
+            cost: 0,
+            proven_optimal: true,
+        });
+    }
+
+    ExactSolver::with_pricing(games, subsets, price)
+        .solve_k_best(1, limits)
+        .into_iter()
+        .next()
+}
+
+/// Plans the subscriptions of a season month by month.
+///
+/// # Overview
+///
+/// Monthly subscriptions can be cancelled at the end of every month, so a user only has to hold a
+/// package during the months its games take place in. The universe is grouped by the calendar
+/// month of each game's `starts_at`, and as there is no cost for switching packages between months,
+/// the cheapest plan consists of the cheapest cover of every single month. These are computed with
+/// the [`ExactSolver`] using the monthly-cancellable prices, so packages without a monthly
+/// subscription are never part of the plan.
+///
+/// For comparison, the cheapest cover of the whole universe with yearly subscriptions is computed
+/// as well. Yearly subscriptions have to be paid for twelve months per started year of the season,
+/// no matter how many months actually have games.
+///
+/// Forced packages, like the ones the user owns or the request requires, are held in every month and
+/// in the yearly plan, and only the games they leave uncovered are searched for. A forced package
+/// without a monthly subscription is held with its yearly one.
+///
+/// # Arguments
+///
+/// * `universe` - The game IDs to plan for.
+/// * `subsets` - The candidate streaming packages.
+/// * `games` - The games of the universe, used to look up their kick-off.
+/// * `forced_package_ids` - The IDs of the packages every month of the plan has to contain.
+/// * `limits` - The search limits shared by all searches.
+///
+/// # Returns
+///
+/// The [`SeasonPlan`], or `None` if none of the games has a valid kick-off.
+///
+pub fn plan_season(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    games: &[GameSchema],
+    forced_package_ids: &[usize],
+    limits: &SearchLimits,
+) -> Option<SeasonPlan> {
+    let mut games_by_month: BTreeMap<&str, BTreeSet<usize>> = BTreeMap::new();
+    for game in games {
+        let game_id = game.game_id as usize;
+        if !universe.contains(&game_id) {
+            continue;
+        }
+
+        if let Some(month) = month_of(&game.starts_at) {
+            games_by_month.entry(month).or_default().insert(game_id);
+        }
+    }
+
+    let first = *games_by_month.keys().next()?;
+    let last = *games_by_month.keys().next_back()?;
+    let years = month_span(first, last)?.div_ceil(MONTHS_PER_YEAR);
+
+    let forced: Vec<&BestCombinationSubsetDto> = subsets
+        .iter()
+        .filter(|s| forced_package_ids.contains(&s.streaming_package_id))
+        .collect();
+    let forced_ids: BTreeSet<usize> = forced.iter().map(|s| s.streaming_package_id).collect();
+    let forced_games: BTreeSet<usize> = forced.iter().flat_map(|s| s.element_ids()).collect();
+    let forced_monthly_price: usize = forced
+        .iter()
+        .map(|s| {
+            s.monthly_price_cents
+                .unwrap_or(s.monthly_price_yearly_subscription_in_cents)
+        })
+        .sum();
+    let forced_yearly_price: usize = forced
+        .iter()
+        .map(|s| s.monthly_price_yearly_subscription_in_cents)
+        .sum();
+
+    let mut proven_optimal = true;
+    let mut package_ids = forced_ids.clone();
+    let mut months = Vec::with_capacity(games_by_month.len());
+
+    for (month, month_games) in &games_by_month {
+        let open_games = month_games - &forced_games;
+        let cheapest = cheapest_cover(&open_games, subsets, |s| s.monthly_price_cents, limits);
+
+        let (ids, price) = match cheapest {
+            Some(solution) => {
+                proven_optimal &= solution.proven_optimal;
+                let mut ids: BTreeSet<usize> = solution.package_ids.into_iter().collect();
+                ids.extend(forced_ids.iter().copied());
+                (
+                    ids.into_iter().collect::<Vec<usize>>(),
+                    forced_monthly_price + solution.cost,
+                )
+            }
+            None => (Vec::new(), 0),
+        };
+
+        let covered_game_count = subsets
+            .iter()
+            .filter(|s| ids.contains(&s.streaming_package_id))
+            .flat_map(|s| s.element_ids())
+            .filter(|id| month_games.contains(id))
+            .collect::<BTreeSet<usize>>()
+            .len();
+
+        package_ids.extend(ids.iter().copied());
+        months.push(MonthlyPlanDto {
+            month: month.to_string(),
+            package_ids: ids,
+            game_count: month_games.len(),
+            covered_game_count,
+            monthly_price_cents: price,
+        });
+    }
+
+    let yearly = cheapest_cover(
+        &(universe - &forced_games),
+        subsets,
+        |s| Some(s.monthly_price_yearly_subscription_in_cents),
+        limits,
+    );
+
+    let (yearly_package_ids, yearly_total_price_cents) = match yearly {
+        Some(solution) => {
+            proven_optimal &= solution.proven_optimal;
+            let total = (forced_yearly_price + solution.cost) * MONTHS_PER_YEAR * years;
+            let mut ids: BTreeSet<usize> = solution.package_ids.into_iter().collect();
+            ids.extend(forced_ids.iter().copied());
+            (ids.into_iter().collect(), Some(total))
+        }
+        None => (Vec::new(), None),
+    };
+
+    Some(SeasonPlan {
+        schedule: SeasonScheduleDto {
+            total_price_cents: months.iter().map(|m| m.monthly_price_cents).sum(),
+            months,
+            yearly_package_ids,
+            yearly_total_price_cents,
+        },
+        package_ids,
+        proven_optimal,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use libs::models::dtos::BestCombinationElementDto;
+    use mongodb::bson::oid;
+
+    fn subset(
+        id: usize,
+        game_ids: &[usize],
+        monthly_price_cents: Option<usize>,
+        yearly_price_cents: usize,
+    ) -> BestCombinationSubsetDto {
+        BestCombinationSubsetDto::new(
+            id,
+            &format!("S{}", id),
+            game_ids
+                .iter()
+                .map(|&game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                .collect(),
+            monthly_price_cents,
+            yearly_price_cents,
+        )
+    }
+
+    fn game(game_id: u32, starts_at: &str) -> GameSchema {
+        GameSchema {
+            id: oid::ObjectId::new(),
+            game_id,
+            team_away: "TEAM A".to_string(),
+            team_home: "TEAM B".to_string(),
+            starts_at: starts_at.to_string(),
+            tournament_name: "TOURNAMENT X".to_string(),
+        }
+    }
+
+    fn limits() -> SearchLimits {
+        SearchLimits::new(Duration::from_secs(10), usize::MAX)
+    }
+
+    #[test]
+    fn test_month_span() {
+        assert_eq!(month_span("2024-08", "2024-08"), Some(1));
+        assert_eq!(month_span("2024-08", "2025-05"), Some(10));
+        assert_eq!(month_span("2024-08", "2025-08"), Some(13));
+    }
+
+    #[test]
+    fn test_plan_season_per_month() {
+        let universe = BTreeSet::from([1, 2, 3]);
+        let subsets = vec![
+            subset(1, &[1, 2, 3], Some(30), 20),
+            subset(2, &[1, 2], Some(10), 8),
+            subset(3, &[3], Some(15), 10),
+        ];
+        let games = vec![
+            game(1, "2024-08-23 20:30:00"),
+            game(2, "2024-08-30 20:30:00"),
+            game(3, "2024-10-05 15:30:00"),
+        ];
+
+        let plan = plan_season(&universe, &subsets, &games, &[], &limits()).unwrap();
+
+        assert_eq!(
+            plan.schedule.months,
+            vec![
+                MonthlyPlanDto {
+                    month: "2024-08".to_string(),
+                    package_ids: vec![2],
+                    game_count: 2,
+                    covered_game_count: 2,
+                    monthly_price_cents: 10,
+                },
+                MonthlyPlanDto {
+                    month: "2024-10".to_string(),
+                    package_ids: vec![3],
+                    game_count: 1,
+                    covered_game_count: 1,
+                    monthly_price_cents: 15,
+                },
+            ]
+        );
+        assert_eq!(plan.schedule.total_price_cents, 25);
+        assert_eq!(plan.schedule.yearly_package_ids, vec![2, 3]);
+        assert_eq!(plan.schedule.yearly_total_price_cents, Some(18 * 12));
+        assert!(plan.schedule.is_cheaper_than_yearly());
+        assert_eq!(plan.package_ids, BTreeSet::from([2, 3]));
+        assert!(plan.proven_optimal);
+    }
+
+    #[test]
+    fn test_plan_season_skips_packages_without_monthly_subscription() {
+        let universe = BTreeSet::from([1]);
+        let subsets = vec![subset(1, &[1], None, 5), subset(2, &[1], Some(70), 30)];
+        let games = vec![game(1, "2024-08-23 20:30:00")];
+
+        let plan = plan_season(&universe, &subsets, &games, &[], &limits()).unwrap();
+
+        assert_eq!(plan.schedule.months[0].package_ids, vec![2]);
+        assert_eq!(plan.schedule.total_price_cents, 70);
+        assert_eq!(plan.schedule.yearly_package_ids, vec![1]);
+        assert_eq!(plan.schedule.yearly_total_price_cents, Some(60));
+        assert!(!plan.schedule.is_cheaper_than_yearly());
+    }
+
+    #[test]
+    fn test_plan_season_without_games() {
+        let universe = BTreeSet::from([1]);
+        let subsets = vec![subset(1, &[1], Some(10), 5)];
+
+        assert!(plan_season(&universe, &subsets, &[], &[], &limits()).is_none());
+    }
+
+    #[test]
+    fn test_plan_season_holds_forced_packages() {
+        let universe = BTreeSet::from([1, 2, 3]);
+        let subsets = vec![
+            subset(1, &[1], None, 12),
+            subset(2, &[1, 2], Some(10), 8),
+            subset(3, &[3], Some(15), 10),
+        ];
+        let games = vec![
+            game(1, "2024-08-23 20:30:00"),
+            game(2, "2024-08-30 20:30:00"),
+            game(3, "2024-10-05 15:30:00"),
+        ];
+
+        let plan = plan_season(&universe, &subsets, &games, &[1], &limits()).unwrap();
+
+        assert_eq!(plan.schedule.months[0].package_ids, vec![1, 2]);
+        assert_eq!(plan.schedule.months[0].monthly_price_cents, 22);
+        assert_eq!(plan.schedule.months[1].package_ids, vec![1, 3]);
+        assert_eq!(plan.schedule.months[1].monthly_price_cents, 27);
+        assert_eq!(plan.schedule.yearly_package_ids, vec![1, 2, 3]);
+        assert_eq!(plan.schedule.yearly_total_price_cents, Some(30 * 12));
+        assert_eq!(plan.package_ids, BTreeSet::from([1, 2, 3]));
+    }
+}
@@ -1,10 +1,151 @@
 use core::f64;
-use std::collections::BTreeSet;
+use std::{
+    collections::BTreeSet,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
-use libs::models::dtos::{BestCombinationDto, BestCombinationSubsetDto};
+use rayon::prelude::*;
 
-use super::mapper;
-use crate::CONFIG;
+use libs::{
+    models::{
+        dtos::{BestCombinationDto, BestCombinationSubsetDto},
+        fetch_types::{CoverageRequirement, PackageFilter, PricingModel},
+        schemas::GameSchema,
+    },
+    solver::SearchLimits,
+};
+
+use super::{
+    bitset::{CoverIndex, Coverage},
+    budget::BudgetSolver,
+    exact::ExactSolver,
+    mapper, pareto, schedule,
+    weights::GameWeights,
+};
+
+/// Number of levels of the greedy search tree which are split into parallel tasks at most.
+const MAX_SPLIT_DEPTH: usize = 2;
+
+/// Restricts the offers of each subset to the ones which satisfy the given coverage requirement.
+///
+/// Offers that don't match (e.g. highlights-only offers when games are required live) are removed,
+/// so the solvers and the mapper only consider matching offers as covering a game. Subsets which are
+/// left without any offer are dropped entirely.
+///
+/// # Arguments
+///
+/// * `subsets` - The candidate streaming packages as aggregated from the database.
+/// * `requirement` - The kind of offer a game has to be available as.
+///
+/// # Returns
+///
+/// `Vec<BestCombinationSubsetDto>`: The subsets reduced to their matching offers.
+///
+pub fn filter_subsets_by_coverage(
+    subsets: Vec<BestCombinationSubsetDto>,
+    requirement: CoverageRequirement,
+) -> Vec<BestCombinationSubsetDto> {
+    subsets
+        .into_iter()
+        .filter_map(|mut subset| {
+            subset.elements.retain(|e| requirement.is_satisfied_by(e));
+            (!subset.elements.is_empty()).then_some(subset)
+        })
+        .collect()
+}
+
+/// Bills every subset according to the given pricing model.
+///
+/// The solvers and the mapper price each subset by the billing option chosen here, so a subset can
+/// be billed monthly while another one of the same request is billed yearly.
+///
+/// # Arguments
+///
+/// * `subsets` - The candidate streaming packages as aggregated from the database.
+/// * `pricing` - The pricing model of the request.
+///
+/// # Returns
+///
+/// `Vec<BestCombinationSubsetDto>`: The subsets with their billing option set.
+///
+pub fn bill_subsets(
+    subsets: Vec<BestCombinationSubsetDto>,
+    pricing: PricingModel,
+) -> Vec<BestCombinationSubsetDto> {
+    subsets
+        .into_iter()
+        .map(|mut subset| {
+            subset.billing = pricing.billing_for(&subset);
+            subset
+        })
+        .collect()
+}
+
+/// Makes the packages the user already owns free of charge.
+///
+/// Owned subsets keep their games but cost nothing under either subscription, so the prices of the
+/// combinations only reflect the additional spending. They are forced into the combinations of
+/// every solver, e.g. via [`reduction::reduce`](super::reduction::reduce) for the covering ones, and
+/// are never stripped as redundant.
+///
+/// # Arguments
+///
+/// * `subsets` - The candidate streaming packages.
+/// * `owned_package_ids` - The IDs of the packages the user already subscribes to.
+///
+/// # Returns
+///
+/// `Vec<BestCombinationSubsetDto>`: The subsets with the owned ones priced at zero.
+///
+pub fn own_subsets(
+    subsets: Vec<BestCombinationSubsetDto>,
+    owned_package_ids: &[usize],
+) -> Vec<BestCombinationSubsetDto> {
+    subsets
+        .into_iter()
+        .map(|mut subset| {
+            if owned_package_ids.contains(&subset.streaming_package_id) {
+                subset.monthly_price_cents = Some(0);
+                subset.monthly_price_yearly_subscription_in_cents = 0;
+            }
+            subset
+        })
+        .collect()
+}
+
+/// Collects the packages every combination has to contain.
+///
+/// These are the packages the user already owns and the ones the package filter of the request
+/// requires. Every solver path forces them into its combinations, and they are never stripped as
+/// redundant.
+///
+/// # Arguments
+///
+/// * `subsets` - The candidate streaming packages.
+/// * `owned_package_ids` - The IDs of the packages the user already subscribes to.
+/// * `package_filter` - The package filter of the request.
+///
+/// # Returns
+///
+/// `Vec<usize>`: The sorted IDs of the forced subsets.
+///
+pub fn forced_package_ids(
+    subsets: &[BestCombinationSubsetDto],
+    owned_package_ids: &[usize],
+    package_filter: &PackageFilter,
+) -> Vec<usize> {
+    let mut package_ids: Vec<usize> = subsets
+        .iter()
+        .filter(|subset| {
+            owned_package_ids.contains(&subset.streaming_package_id)
+                || package_filter.requires(subset.streaming_package_id, &subset.name)
+        })
+        .map(|subset| subset.streaming_package_id)
+        .collect();
+    package_ids.sort();
+    package_ids.dedup();
+    package_ids
+}
 
 /// Computes a set of best combinations of streaming package subsets that cover a given universe of game IDs.
 ///
@@ -18,9 +159,13 @@ use crate::CONFIG;
 /// approximate an arbitrary number of solutions, which get as close as possible.
 ///
 /// Under the hood, this method uses a greedy recursive backtracking strategy, guided by heuristics like
-/// sorting subsets according to cost or cost-per-uncovered-element ratios. While heuristics and pruning
+/// sorting subsets according to cost or cost-per-uncovered-element ratios. The uncovered elements are
+/// counted with their `weights`, so packages offering high-priority games are tried first. Every
+/// combination found still covers all offered games, which satisfies the must-covers as well, but the
+/// closest combination returned once the `limits` are exhausted favours the high-priority games. While heuristics and pruning
 /// strategies may help in practice, the underlying problem is NP-hard. Thus, this algorithm can still
-/// exhibit exponential runtime in the worst case.
+/// exhibit exponential runtime in the worst case. The search is therefore bounded by `limits`. If they
+/// are exhausted, the combinations found so far are returned with `timed_out` set.
 ///
 /// # Example Scenario
 ///
@@ -38,6 +183,8 @@ use crate::CONFIG;
 /// * `universe` - A `BTreeSet<usize>` representing all game IDs that must be covered.
 /// * `subsets` - A slice of `BestCombinationSubsetDto` representing candidate streaming packages.
 /// * `limit` - The maximum number of solutions (combinations of subsets) to return.
+/// * `weights` - The weights of the games, which guide the order the packages are tried in.
+/// * `limits` - The deadline and node budget of the search.
 ///
 /// # Returns
 ///
@@ -47,13 +194,605 @@ pub fn get_best_combinations(
     universe: &BTreeSet<usize>,
     subsets: &[BestCombinationSubsetDto],
     limit: usize,
+    weights: &GameWeights,
+    limits: &SearchLimits,
 ) -> Vec<BestCombinationDto> {
     let mut results: Vec<BestCombinationDto> = Vec::new();
     let mut current_cover: Vec<usize> = Vec::new();
-    enumerate_best_combinations(universe, subsets, limit, &mut results, &mut current_cover);
+    let index = CoverIndex::new(universe, subsets, weights);
+    let mut coverage = Coverage::new(&index);
+    enumerate_best_combinations(
+        universe,
+        subsets,
+        limit,
+        &mut results,
+        &mut current_cover,
+        &mut coverage,
+        limits,
+        None,
+    );
+
+    if limits.is_exhausted() {
+        results.iter_mut().for_each(|r| r.timed_out = true);
+    }
+    results
+}
+
+/// Computes the combinations of [`get_best_combinations`], but explores the search tree on the rayon thread
+/// pool.
+///
+/// # Overview
+///
+/// The branches of the search tree are independent of each other: which combinations a branch records, and in
+/// which order, only depends on its own path. The first levels of the tree (up to [`MAX_SPLIT_DEPTH`]) are
+/// therefore split into one task per node, kept in the order the sequential search visits them. Each task
+/// searches its subtree for up to `limit` combinations.
+///
+/// The tasks are run in chunks of the pool's size, and their combinations are merged in task order, skipping
+/// duplicates of earlier ones, until `limit` combinations have been collected. If duplicates leave a task short of
+/// combinations, it is searched again for more. This yields exactly the results of the sequential search, while
+/// at most one chunk of tasks is explored in vain. All tasks share the same `limits`. See
+/// [`get_cheapest_first_combination`] for a search which prunes the tasks against each other instead.
+///
+/// # Arguments
+///
+/// * `universe` - A `BTreeSet<usize>` representing all game IDs that must be covered.
+/// * `subsets` - A slice of `BestCombinationSubsetDto` representing candidate streaming packages.
+/// * `limit` - The maximum number of solutions (combinations of subsets) to return.
+/// * `weights` - The weights of the games, which guide the order the packages are tried in.
+/// * `limits` - The deadline and node budget shared by all tasks.
+///
+/// # Returns
+///
+/// `Vec<BestCombinationDto>`: A vector of best combinations.
+///
+pub fn get_best_combinations_parallel(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    limit: usize,
+    weights: &GameWeights,
+    limits: &SearchLimits,
+) -> Vec<BestCombinationDto> {
+    let index = CoverIndex::new(universe, subsets, weights);
+    let threads = rayon::current_num_threads();
+    let tasks = split_search_tree(subsets, &index, threads);
+
+    let mut results: Vec<BestCombinationDto> = Vec::new();
+    'merge: for chunk in tasks.chunks(threads) {
+        let found: Vec<Vec<BestCombinationDto>> = chunk
+            .par_iter()
+            .map(|prefix| search_subtree(universe, subsets, &index, prefix, limit, limits, None))
+            .collect();
+
+        for (prefix, mut combinations) in chunk.iter().zip(found) {
+            let mut task_limit = limit;
+            let mut merged = 0;
+
+            loop {
+                for combination in combinations.drain(merged..) {
+                    merged += 1;
+                    if !results.iter().any(|r| r.is_duplicate_of(&combination)) {
+                        results.push(combination);
+                        if results.len() >= limit {
+                            break 'merge;
+                        }
+                    }
+                }
+
+                // Only a task which has been stopped by its limit can have more combinations
+                if merged < task_limit || limits.is_exhausted() {
+                    break;
+                }
+                task_limit *= 2;
+                combinations =
+                    search_subtree(universe, subsets, &index, prefix, task_limit, limits, None);
+            }
+        }
+    }
+
+    for (index, result) in results.iter_mut().enumerate() {
+        result.index = index;
+        result.timed_out = limits.is_exhausted();
+    }
+    results
+}
+
+/// Searches every top-level branch of the search of [`get_best_combinations`] for its first cover and
+/// returns the cheapest one.
+///
+/// # Overview
+///
+/// The search of [`get_best_combinations`] follows the best-ranked package first, so its first cover
+/// can be more expensive than the first cover of another top-level branch. This search starts a task
+/// from every top-level branch instead. The tasks share the cost of the cheapest cover found by any
+/// of them and prune the nodes which already cost as much, so a task whose first covers are expensive
+/// keeps searching for cheaper ones while the others can skip them. The cheapest cover found is
+/// returned, ties going to the earlier task. If no task covers all games, the closest combination of
+/// the first task is returned like in the sequential search.
+///
+/// The returned cover is never more expensive than the first one of [`get_best_combinations`], but
+/// may differ from it. In parallel, which nodes are pruned depends on when the tasks find their
+/// covers, so the cover may also differ between runs. It is therefore a solver of its own, which has
+/// to be selected explicitly.
+///
+/// # Arguments
+///
+/// * `universe` - A `BTreeSet<usize>` representing all game IDs that must be covered.
+/// * `subsets` - A slice of `BestCombinationSubsetDto` representing candidate streaming packages.
+/// * `weights` - The weights of the games, which guide the order the packages are tried in.
+/// * `limits` - The deadline and node budget shared by all tasks.
+/// * `parallel` - Whether to search the tasks on the rayon thread pool.
+///
+/// # Returns
+///
+/// `Vec<BestCombinationDto>`: The cheapest combination found, if any.
+///
+pub fn get_cheapest_first_combination(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    weights: &GameWeights,
+    limits: &SearchLimits,
+    parallel: bool,
+) -> Vec<BestCombinationDto> {
+    let index = CoverIndex::new(universe, subsets, weights);
+    // Splitting until there are two tasks splits exactly the root, if it has several branches
+    let tasks = split_search_tree(subsets, &index, 2);
+    let search = |prefix: &Vec<usize>, best_cost: &AtomicUsize| {
+        search_subtree(
+            universe,
+            subsets,
+            &index,
+            prefix,
+            1,
+            limits,
+            Some(best_cost),
+        )
+    };
+
+    let best_cost = AtomicUsize::new(usize::MAX);
+    let found: Vec<Vec<BestCombinationDto>> = if parallel {
+        tasks
+            .par_iter()
+            .map(|prefix| search(prefix, &best_cost))
+            .collect()
+    } else {
+        tasks
+            .iter()
+            .map(|prefix| search(prefix, &best_cost))
+            .collect()
+    };
+
+    let covers_universe = |combination: &BestCombinationDto| {
+        let covered: BTreeSet<usize> = subsets
+            .iter()
+            .filter(|s| {
+                combination
+                    .packages
+                    .iter()
+                    .any(|p| p.id == s.streaming_package_id)
+            })
+            .flat_map(|s| s.element_ids())
+            .collect();
+        universe.is_subset(&covered)
+    };
+
+    let found: Vec<BestCombinationDto> = found.into_iter().flatten().collect();
+    let cheapest = found
+        .iter()
+        .filter(|c| covers_universe(c))
+        .min_by_key(|c| {
+            let package_ids: Vec<usize> = c.packages.iter().map(|p| p.id).collect();
+            billed_cost(subsets, &package_ids)
+        })
+        .or(found.first());
+
+    let mut results: Vec<BestCombinationDto> = cheapest.into_iter().cloned().collect();
+    for result in results.iter_mut() {
+        result.index = 0;
+        result.timed_out = limits.is_exhausted();
+    }
+    results
+}
+
+/// Seeds the combinations of a greedy search with the cover of a similar request, which has been
+/// solved before.
+///
+/// # Overview
+///
+/// The cover is repaired for the given universe first: its packages which are still offered and
+/// add coverage are kept, and the games left uncovered are covered by the best-ranked packages, like
+/// the search of [`get_best_combinations`] would. The repaired cover is the incumbent, which stays
+/// the first combination unless the search found a cheaper one. So a request which only differs from
+/// a solved one by a few games doesn't get a more expensive combination than the solved one, just
+/// because the greedy search took another path.
+///
+/// The repaired cover covers every offered game, so it also replaces a first combination which the
+/// search only found before its `limits` have been exhausted.
+///
+/// # Arguments
+///
+/// * `universe` - A `BTreeSet<usize>` representing all game IDs that must be covered.
+/// * `subsets` - A slice of `BestCombinationSubsetDto` representing candidate streaming packages.
+/// * `limit` - The maximum number of solutions (combinations of subsets) to return.
+/// * `weights` - The weights of the games, which guide the order the packages are tried in.
+/// * `combinations` - The combinations found by the search, in the order of their rank.
+/// * `warm_start` - The package IDs of a cover of a similar universe.
+///
+/// # Returns
+///
+/// `Vec<BestCombinationDto>`: The combinations, starting with the repaired cover if it is better.
+///
+pub fn seed_combinations(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    limit: usize,
+    weights: &GameWeights,
+    combinations: Vec<BestCombinationDto>,
+    warm_start: &[usize],
+) -> Vec<BestCombinationDto> {
+    let index = CoverIndex::new(universe, subsets, weights);
+    let mut coverage = Coverage::new(&index);
+    let mut cover: Vec<usize> = Vec::new();
+
+    for (i, subset) in subsets.iter().enumerate() {
+        let id = subset.streaming_package_id;
+        if warm_start.contains(&id)
+            && !cover.contains(&id)
+            && subset.billed_price_cents().is_some()
+            && coverage.fresh_count(i) > 0
+        {
+            coverage.add(i);
+            cover.push(id);
+        }
+    }
+    while let Some(&i) = rank_candidates(subsets, &coverage).first() {
+        coverage.add(i);
+        cover.push(subsets[i].streaming_package_id);
+    }
+    if cover.is_empty() {
+        return combinations;
+    }
+
+    let seeded = mapper::map_to_best_combination_dto(&cover, subsets, universe, 0);
+    let is_better = combinations.first().is_none_or(|first| {
+        let package_ids: Vec<usize> = first.packages.iter().map(|p| p.id).collect();
+        first.timed_out || billed_cost(subsets, &cover) < billed_cost(subsets, &package_ids)
+    });
+    if !is_better {
+        return combinations;
+    }
+
+    let mut results = vec![seeded];
+    for combination in combinations {
+        if results.len() < limit && !results.iter().any(|r| r.is_duplicate_of(&combination)) {
+            results.push(combination);
+        }
+    }
+    for (index, result) in results.iter_mut().enumerate() {
+        result.index = index;
+    }
+    results
+}
+
+/// Splits the first [`MAX_SPLIT_DEPTH`] levels of the greedy search tree into the paths of the subtrees to search
+/// in parallel, in the order of the sequential search. A level is only split if there are fewer than `min_tasks`
+/// tasks yet, and nodes which can't be expanded are kept as tasks of their own.
+fn split_search_tree(
+    subsets: &[BestCombinationSubsetDto],
+    index: &CoverIndex,
+    min_tasks: usize,
+) -> Vec<Vec<usize>> {
+    let mut tasks: Vec<Vec<usize>> = vec![Vec::new()];
+
+    for _ in 0..MAX_SPLIT_DEPTH {
+        if tasks.len() >= min_tasks {
+            break;
+        }
+
+        tasks = tasks
+            .into_iter()
+            .flat_map(|prefix| {
+                let mut coverage = Coverage::new(index);
+                prefix.iter().for_each(|&i| {
+                    coverage.add(i);
+                });
+
+                let candidates = if is_leaf(subsets, &prefix, &coverage) {
+                    Vec::new()
+                } else {
+                    rank_candidates(subsets, &coverage)
+                };
+
+                if candidates.is_empty() {
+                    vec![prefix]
+                } else {
+                    candidates
+                        .into_iter()
+                        .map(|i| [prefix.as_slice(), &[i]].concat())
+                        .collect()
+                }
+            })
+            .collect();
+    }
+
+    tasks
+}
+
+/// Searches the subtree below the given path of subset indices for up to `limit` combinations, pruning nodes
+/// against the shared `best_cost` if there is one.
+fn search_subtree(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    index: &CoverIndex,
+    prefix: &[usize],
+    limit: usize,
+    limits: &SearchLimits,
+    best_cost: Option<&AtomicUsize>,
+) -> Vec<BestCombinationDto> {
+    let mut coverage = Coverage::new(index);
+    prefix.iter().for_each(|&i| {
+        coverage.add(i);
+    });
+
+    let mut results: Vec<BestCombinationDto> = Vec::new();
+    let mut current_cover: Vec<usize> = prefix
+        .iter()
+        .map(|&i| subsets[i].streaming_package_id)
+        .collect();
+    enumerate_best_combinations(
+        universe,
+        subsets,
+        limit,
+        &mut results,
+        &mut current_cover,
+        &mut coverage,
+        limits,
+        best_cost,
+    );
     results
 }
 
+/// Computes the cheapest combinations of streaming package subsets that cover a given universe of game IDs.
+///
+/// # Overview
+///
+/// Unlike [`get_best_combinations`], this method runs an exact branch-and-bound search (see
+/// [`ExactSolver`]) which proves that no cheaper cover exists. Games which aren't offered by any
+/// bookable package are left out, so the results are the cheapest covers of everything coverable.
+///
+/// The `limit` cheapest distinct covers are enumerated with Lawler's partitioning scheme and returned
+/// in ascending order of their combined price, so `index` reflects the rank of a combination. Ties
+/// are broken by the package IDs.
+///
+/// As the problem is NP-hard, the search is bounded by `limits`. If they are exhausted, the best covers
+/// found so far are returned with `timed_out` set, and `proven_optimal` is set to `false` on the
+/// affected results.
+///
+/// # Arguments
+///
+/// * `universe` - A `BTreeSet<usize>` representing all game IDs that must be covered.
+/// * `subsets` - A slice of `BestCombinationSubsetDto` representing candidate streaming packages.
+/// * `limit` - The maximum number of solutions (combinations of subsets) to return.
+/// * `limits` - The deadline and node budget of the search.
+/// * `parallel` - Whether to search the first levels of the search trees on the rayon thread pool. The
+///   results are the same as those of the sequential search.
+/// * `warm_start` - The package IDs of a cover of a similar universe to seed the searches with, see
+///   [`ExactSolver::warm_start`]. May be empty.
+///
+/// # Returns
+///
+/// `Vec<BestCombinationDto>`: A vector of the cheapest combinations, ranked by price.
+///
+pub fn get_optimal_combinations(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    limit: usize,
+    limits: &SearchLimits,
+    parallel: bool,
+    warm_start: &[usize],
+) -> Vec<BestCombinationDto> {
+    let solutions = ExactSolver::new(universe, subsets)
+        .parallel(parallel)
+        .warm_start(warm_start)
+        .report_costs(true)
+        .solve_k_best(limit, limits);
+
+    solutions
+        .into_iter()
+        .enumerate()
+        .map(|(index, solution)| {
+            let mut result = mapper::map_to_best_combination_dto(
+                &solution.package_ids,
+                subsets,
+                universe,
+                index,
+            );
+            result.proven_optimal = solution.proven_optimal;
+            result.timed_out = limits.is_exhausted();
+            result
+        })
+        .collect()
+}
+
+/// Computes the combinations of streaming package subsets that cover the most games within a budget.
+///
+/// # Overview
+///
+/// Instead of covering the whole universe as cheaply as possible, this method fixes the price and
+/// maximizes the coverage: it returns the combinations whose combined price doesn't exceed `budget`
+/// and which cover the most games of the universe, i.e. have the highest `combined_coverage`.
+/// Combinations with the same coverage are ranked by their price. See [`BudgetSolver`] for the
+/// branch-and-bound search behind it.
+///
+/// If `weights` prioritise some games, the combined weight of the covered games is maximized
+/// instead, and combinations which miss a must-cover game are left out.
+///
+/// The number of packages per combination can be capped with `max_packages`. Without a budget,
+/// this yields the cheapest covers with at most that many packages or, if none exists, the
+/// combinations which come closest to a cover.
+///
+/// As the problem is NP-hard, the search is bounded by `limits`. If they are exhausted, the best
+/// combinations found so far are returned with `timed_out` set and `proven_optimal` set to `false`.
+///
+/// # Arguments
+///
+/// * `universe` - A `BTreeSet<usize>` representing all requested game IDs.
+/// * `subsets` - A slice of `BestCombinationSubsetDto` representing candidate streaming packages.
+/// * `limit` - The maximum number of solutions (combinations of subsets) to return.
+/// * `budget` - The maximum combined monthly price in cents.
+/// * `max_packages` - The maximum number of packages per combination, if any.
+/// * `forced_package_ids` - The IDs of the packages every combination has to contain.
+/// * `weights` - The weights and must-cover constraints of the games.
+/// * `limits` - The deadline and node budget of the search.
+///
+/// # Returns
+///
+/// `Vec<BestCombinationDto>`: A vector of combinations within the budget, ranked by coverage.
+///
+#[allow(clippy::too_many_arguments)]
+pub fn get_budgeted_combinations(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    limit: usize,
+    budget: usize,
+    max_packages: Option<usize>,
+    forced_package_ids: &[usize],
+    weights: &GameWeights,
+    limits: &SearchLimits,
+) -> Vec<BestCombinationDto> {
+    let solutions = BudgetSolver::new(universe, subsets, budget, weights, forced_package_ids)
+        .max_packages(max_packages.unwrap_or(usize::MAX))
+        .solve(limit, limits);
+
+    solutions
+        .into_iter()
+        .enumerate()
+        .map(|(index, solution)| {
+            let mut result = mapper::map_to_best_combination_dto(
+                &solution.package_ids,
+                subsets,
+                universe,
+                index,
+            );
+            result.proven_optimal = solution.proven_optimal;
+            result.timed_out = limits.is_exhausted();
+            result
+        })
+        .collect()
+}
+
+/// Computes the Pareto-optimal combinations of streaming package subsets over their price and coverage.
+///
+/// # Overview
+///
+/// Instead of a single trade-off, this method returns every combination for which no other
+/// combination covers as many games for less, so a user can weigh e.g. full coverage against a
+/// much cheaper combination that misses a few games. The frontier is traced with repeated budgeted
+/// searches, see [`pareto::pareto_frontier`]. Its combinations are ranked by ascending price, and
+/// thus ascending coverage, and their number isn't bounded by a limit.
+///
+/// If `weights` prioritise some games, their combined weight is traded off against the price
+/// instead, and combinations which miss a must-cover game are left out.
+///
+/// As the problem is NP-hard, the searches are bounded by `limits`. If they are exhausted, the
+/// frontier found so far is returned with `timed_out` set.
+///
+/// # Arguments
+///
+/// * `universe` - A `BTreeSet<usize>` representing all requested game IDs.
+/// * `subsets` - A slice of `BestCombinationSubsetDto` representing candidate streaming packages.
+/// * `budget` - The maximum combined monthly price in cents.
+/// * `max_packages` - The maximum number of packages per combination, if any.
+/// * `forced_package_ids` - The IDs of the packages every combination has to contain.
+/// * `weights` - The weights and must-cover constraints of the games.
+/// * `limits` - The deadline and node budget shared by the searches.
+///
+/// # Returns
+///
+/// `Vec<BestCombinationDto>`: A vector of the Pareto-optimal combinations, ranked by price.
+///
+pub fn get_pareto_combinations(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    budget: usize,
+    max_packages: Option<usize>,
+    forced_package_ids: &[usize],
+    weights: &GameWeights,
+    limits: &SearchLimits,
+) -> Vec<BestCombinationDto> {
+    let frontier = pareto::pareto_frontier(
+        universe,
+        subsets,
+        budget,
+        max_packages.unwrap_or(usize::MAX),
+        forced_package_ids,
+        weights,
+        limits,
+    );
+
+    frontier
+        .into_iter()
+        .enumerate()
+        .map(|(index, solution)| {
+            let mut result = mapper::map_to_best_combination_dto(
+                &solution.package_ids,
+                subsets,
+                universe,
+                index,
+            );
+            result.proven_optimal = solution.proven_optimal;
+            result.timed_out = limits.is_exhausted();
+            result
+        })
+        .collect()
+}
+
+/// Computes a month-by-month subscription plan for a given universe of game IDs.
+///
+/// # Overview
+///
+/// Instead of holding every package for the whole season, the plan only subscribes to the cheapest
+/// monthly-cancellable packages during the months the games take place in (see
+/// [`schedule::plan_season`]). The result is a single combination of all packages held at some
+/// point of the season, with the month-by-month plan attached as its `schedule`. The plan also
+/// compares its total cost against the cheapest yearly subscriptions for the season.
+///
+/// As the problem is NP-hard, the searches are bounded by `limits`. If they are exhausted, the best
+/// plan found so far is returned with `timed_out` set and `proven_optimal` set to `false`.
+///
+/// # Arguments
+///
+/// * `universe` - A `BTreeSet<usize>` representing all game IDs that must be covered.
+/// * `subsets` - A slice of `BestCombinationSubsetDto` representing candidate streaming packages.
+/// * `games` - The games of the universe, providing their kick-off.
+/// * `forced_package_ids` - The IDs of the packages held in every month of the plan.
+/// * `limits` - The deadline and node budget shared by the searches.
+///
+/// # Returns
+///
+/// `Vec<BestCombinationDto>`: The scheduled combination, or an empty vector if no plan exists.
+///
+pub fn get_scheduled_combinations(
+    universe: &BTreeSet<usize>,
+    subsets: &[BestCombinationSubsetDto],
+    games: &[GameSchema],
+    forced_package_ids: &[usize],
+    limits: &SearchLimits,
+) -> Vec<BestCombinationDto> {
+    let Some(plan) = schedule::plan_season(universe, subsets, games, forced_package_ids, limits)
+    else {
+        return Vec::new();
+    };
+
+    let package_ids: Vec<usize> = plan.package_ids.into_iter().collect();
+    let mut result = mapper::map_to_best_combination_dto(&package_ids, subsets, universe, 0);
+    result.proven_optimal = plan.proven_optimal;
+    result.timed_out = limits.is_exhausted();
+    result.schedule = Some(plan.schedule);
+
+    vec![result]
+}
+
 /// Recursively enumerates possible combinations of subsets that cover the given universe of game IDs.
 ///
 /// # Overview
@@ -67,6 +806,13 @@ pub fn get_best_combinations(
 /// 4. The function then attempts to find more solutions (up to the specified `limit`) by backtracking and trying
 ///    alternate subsets.
 ///
+/// The search runs on the [`CoverIndex`] of the universe, so evaluating a candidate only counts the bits of
+/// its games not yet covered, and choosing or dropping a candidate only sets or clears its newly covered games.
+///
+/// Every call accounts for one node of the search `limits`. Once they are exhausted, the search halts. If no
+/// solution has been found at that point, the current partial cover is recorded as the closest one. The
+/// billed cost of every full cover found is recorded in the `limits` as the progress of the search.
+///
 /// # Arguments
 ///
 /// * `universe` - The full set of game IDs that must be covered.
@@ -74,37 +820,59 @@ pub fn get_best_combinations(
 /// * `limit` - The maximum number of solutions to return. Once reached, the search halts.
 /// * `results` - A mutable reference to a vector collecting all found solutions.
 /// * `current_cover` - A mutable vector representing the current partial solution (as a list of chosen subset IDs).
+/// * `coverage` - The games covered by `current_cover`, maintained incrementally while backtracking.
+/// * `limits` - The deadline and node budget of the search.
+/// * `best_cost` - The billed cost of the cheapest cover found by any search sharing it. If given, nodes which
+///   already cost as much are pruned, and the cost of every cover found is recorded in it.
 ///
 /// # Returns
 ///
 /// Returns `true` if more solutions can still be found (meaning it will continue searching), or `false`
 /// if the limit has been reached or no further solutions are possible.
 ///
+#[allow(clippy::too_many_arguments)]
 fn enumerate_best_combinations(
     universe: &BTreeSet<usize>,
     subsets: &[BestCombinationSubsetDto],
     limit: usize,
     results: &mut Vec<BestCombinationDto>,
     current_cover: &mut Vec<usize>,
+    coverage: &mut Coverage,
+    limits: &SearchLimits,
+    best_cost: Option<&AtomicUsize>,
 ) -> bool {
-    let covered: BTreeSet<usize> = current_cover
-        .iter()
-        .flat_map(|&id| {
-            subsets
-                .iter()
-                .find(|s| s.streaming_package_id == id)
-                .unwrap()
-                .elements
-                .iter()
-                .map(|elem| elem.game_id)
-        })
-        .collect();
+    // Stop if the search limits are exhausted, keeping the closest cover if nothing has been found yet
+    if !limits.explore() {
+        if results.is_empty() && !current_cover.is_empty() {
+            results.push(mapper::map_to_best_combination_dto(
+                current_cover,
+                subsets,
+                universe,
+                0,
+            ));
+        }
+        return true;
+    }
+
+    // Adding packages only makes a cover more expensive, so it can't beat the cheapest one anymore
+    if let Some(best_cost) = best_cost {
+        if billed_cost(subsets, current_cover) >= best_cost.load(Ordering::Relaxed) {
+            return false;
+        }
+    }
 
     // Check if all elements are covered or if a leaf node has been reached
-    if covered == *universe || current_cover.len() >= subsets.len() {
+    if is_leaf(subsets, current_cover, coverage) {
         let result =
             mapper::map_to_best_combination_dto(current_cover, subsets, universe, results.len());
         if !results.iter().any(|r| r.is_duplicate_of(&result)) {
+            if coverage.is_complete() {
+                let cost = billed_cost(subsets, current_cover);
+                limits.record_cost(cost);
+                if let Some(best_cost) = best_cost {
+                    best_cost.fetch_min(cost, Ordering::Relaxed);
+                }
+            }
             results.push(result);
             if results.len() >= limit {
                 return true; // Signal to stop further recursion
@@ -113,45 +881,31 @@ fn enumerate_best_combinations(
         return false; // Continue searching if limit not reached
     }
 
-    // Calculate cost-benefit ratio for each subset based on uncovered elements
-    let mut ratios: Vec<(usize, f64)> = subsets
-        .iter()
-        .enumerate()
-        .filter_map(|(i, s)| {
-            let uncovered_elements = s.element_ids().difference(&covered).count();
-
-            if uncovered_elements > 0 {
-                let cost = if CONFIG.use_yearly_price {
-                    s.monthly_price_yearly_subscription_in_cents as f64
-                } else {
-                    // Use a high value if monthly_price_cents is None to effectively exclude this subset
-                    s.monthly_price_cents.unwrap_or(usize::MAX) as f64
-                };
-                Some((i, cost / uncovered_elements as f64))
-            } else {
-                None // skip subsets that don't add coverage
-            }
-        })
-        .collect();
-
-    // Sort subsets based on ascending ratio (lower is better)
-    ratios.sort_by(|(_, ratio1), (_, ratio2)| {
-        ratio1
-            .partial_cmp(ratio2)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+    let candidates = rank_candidates(subsets, coverage);
 
     let mut branch_explored = true;
 
-    for (i, _) in ratios.iter() {
-        current_cover.push(subsets[*i].streaming_package_id);
+    for i in candidates {
+        // Only keep track of the newly covered games, so the step can be undone
+        let fresh = coverage.add(i);
+        current_cover.push(subsets[i].streaming_package_id);
 
         // Recurse and check if it should step
-        if enumerate_best_combinations(universe, subsets, limit, results, current_cover) {
+        if enumerate_best_combinations(
+            universe,
+            subsets,
+            limit,
+            results,
+            current_cover,
+            coverage,
+            limits,
+            best_cost,
+        ) {
             return true;
         };
 
         current_cover.pop();
+        coverage.undo(&fresh);
 
         // If it exits here, the branch has been fully explored
         branch_explored = false;
@@ -174,15 +928,75 @@ fn enumerate_best_combinations(
     false // Continue searching
 }
 
+/// Sums up the billed prices of the chosen packages.
+fn billed_cost(subsets: &[BestCombinationSubsetDto], package_ids: &[usize]) -> usize {
+    subsets
+        .iter()
+        .filter(|s| package_ids.contains(&s.streaming_package_id))
+        .filter_map(BestCombinationSubsetDto::billed_price_cents)
+        .sum()
+}
+
+/// Checks whether all games are covered or every subset has been chosen, so a node can't be expanded.
+fn is_leaf(
+    subsets: &[BestCombinationSubsetDto],
+    current_cover: &[usize],
+    coverage: &Coverage,
+) -> bool {
+    coverage.is_complete() || current_cover.len() >= subsets.len()
+}
+
+/// Ranks the subsets which add coverage to a node by their cost per weight of the uncovered games,
+/// which is the order the greedy search branches in.
+fn rank_candidates(subsets: &[BestCombinationSubsetDto], coverage: &Coverage) -> Vec<usize> {
+    // Calculate cost-benefit ratio for each subset based on uncovered elements
+    let mut ratios: Vec<(usize, f64)> = subsets
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| {
+            let uncovered_elements = coverage.fresh_weight(i);
+
+            if uncovered_elements > 0 {
+                // Use a high value if the subset can't be booked to effectively exclude it
+                let cost = s.billed_price_cents().unwrap_or(usize::MAX) as f64;
+                Some((i, cost / uncovered_elements as f64))
+            } else {
+                None // skip subsets that don't add coverage
+            }
+        })
+        .collect();
+
+    // Sort subsets based on ascending ratio (lower is better)
+    ratios.sort_by(|(_, ratio1), (_, ratio2)| {
+        ratio1
+            .partial_cmp(ratio2)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    ratios.into_iter().map(|(i, _)| i).collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use std::{collections::BTreeMap, time::Duration};
+
     use super::*;
     use crate::config::CONFIG;
+    use crate::core::{reduction, redundancy};
     use libs::{
         constants::{DATABASE_NAME, STREAMING_PACKAGE_COLLECTION_NAME},
         db::{dao::StreamingPackageDao, DocumentDatabaseConnector, MongoClient},
-        models::dtos::{BestCombinationElementDto, BestCombinationPackageDto},
+        models::{
+            dtos::{BestCombinationElementDto, BestCombinationPackageDto},
+            fetch_types::{BillingOption, PackageFilter},
+            schemas::GameSchema,
+        },
     };
+    use mongodb::bson::oid;
+
+    fn limits() -> SearchLimits {
+        SearchLimits::new(Duration::from_secs(10), usize::MAX)
+    }
 
     async fn setup_data() -> (Vec<usize>, Vec<BestCombinationSubsetDto>) {
         dotenv::dotenv().ok();
@@ -202,7 +1016,9 @@ mod tests {
             5557, 5566, 5584, 5573, 5593, 7354, 7890, 8440, 8466, 8486, 8514, 8503, 8533, 8568,
             8560, 8845,
         ];
-        let subsets = package_dao.aggregate_subsets_by_game_ids(&game_ids).await;
+        let subsets = package_dao
+            .aggregate_subsets_by_game_ids(&game_ids, &PackageFilter::default())
+            .await;
 
         assert!(subsets.is_ok());
 
@@ -217,7 +1033,13 @@ mod tests {
         let limit = 5;
 
         let expected_cover = vec![BestCombinationDto::new(vec![], 0, 0, 0, 0)];
-        let results = get_best_combinations(&universe, &subsets, limit);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
         assert_eq!(results, expected_cover);
     }
 
@@ -239,7 +1061,13 @@ mod tests {
         let limit = 5;
 
         let expected_cover = vec![BestCombinationDto::new(vec![], 0, 0, 0, 0)];
-        let results = get_best_combinations(&universe, &subsets, limit);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
         assert_eq!(results, expected_cover);
     }
 
@@ -251,12 +1079,18 @@ mod tests {
         let limit = 2;
 
         let expected_cover = vec![BestCombinationDto::new(vec![], 0, 0, 0, 0)];
-        let results = get_best_combinations(&universe, &subsets, limit);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
         assert_eq!(results, expected_cover);
     }
 
     #[test]
-    fn test_configuration_flag() {
+    fn test_pricing_model() {
         dotenv::dotenv().ok();
         let universe = BTreeSet::from([1]);
         let subsets = vec![
@@ -274,24 +1108,59 @@ mod tests {
                 Some(100),
                 10,
             ),
+            BestCombinationSubsetDto::new(
+                3,
+                "S3",
+                BTreeSet::from([BestCombinationElementDto::new(1, "A", 1, 1)]),
+                None,
+                50,
+            ),
         ];
-        let limit = 1;
+        let limit = 3;
 
-        let expected_cover = vec![BestCombinationDto::new(
-            vec![BestCombinationPackageDto::new(
-                1,
-                "S1",
-                vec![("A", (2, 2))],
-                Some(10),
-                100,
-            )],
-            10,
-            100,
-            100,
-            0,
-        )];
-        let results = get_best_combinations(&universe, &subsets, limit);
-        assert_eq!(results, expected_cover);
+        let monthly = get_best_combinations(
+            &universe,
+            &bill_subsets(subsets.clone(), PricingModel::Monthly),
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
+        let ids: Vec<usize> = monthly.iter().map(|r| r.packages[0].id).collect();
+        // Packages without a monthly subscription are only used as a last resort
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        let yearly = get_best_combinations(
+            &universe,
+            &bill_subsets(subsets.clone(), PricingModel::Yearly),
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
+        let ids: Vec<usize> = yearly.iter().map(|r| r.packages[0].id).collect();
+        assert_eq!(ids, vec![2, 3, 1]);
+        assert!(yearly
+            .iter()
+            .all(|r| r.packages[0].billing == BillingOption::Yearly));
+
+        let cheapest = get_best_combinations(
+            &universe,
+            &bill_subsets(subsets, PricingModel::CheapestOfBoth),
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
+        let billed: Vec<(usize, BillingOption)> = cheapest
+            .iter()
+            .map(|r| (r.packages[0].id, r.packages[0].billing))
+            .collect();
+        assert_eq!(
+            billed,
+            vec![
+                (1, BillingOption::Monthly),
+                (2, BillingOption::Yearly),
+                (3, BillingOption::Yearly),
+            ]
+        );
     }
 
     #[test]
@@ -324,7 +1193,13 @@ mod tests {
             100,
             0,
         )];
-        let results = get_best_combinations(&universe, &subsets, limit);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
         assert_eq!(results, expected_cover);
     }
 
@@ -361,7 +1236,13 @@ mod tests {
             67,
             0,
         )];
-        let results = get_best_combinations(&universe, &subsets, limit);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
         assert_eq!(
             results, expected_cover,
             "Should find the next best coverage approximation"
@@ -403,7 +1284,13 @@ mod tests {
             100,
             0,
         )];
-        let results = get_best_combinations(&universe, &subsets, limit);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
         assert!(results.len() == 1);
         assert_eq!(
             results, expected_cover,
@@ -464,10 +1351,279 @@ mod tests {
                 1,
             ),
         ];
-        let results = get_best_combinations(&universe, &subsets, limit);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
         assert_eq!(results, expected_cover);
     }
 
+    #[test]
+    fn test_optimal_combination() {
+        dotenv::dotenv().ok();
+        let universe: BTreeSet<_> = (1..=6).collect();
+        let element = |id| BestCombinationElementDto::new(id, "", 1, 1);
+        let subsets = vec![
+            BestCombinationSubsetDto::new(
+                1,
+                "S1",
+                BTreeSet::from([element(1), element(2), element(3), element(4)]),
+                Some(40),
+                40,
+            ),
+            BestCombinationSubsetDto::new(
+                2,
+                "S2",
+                BTreeSet::from([element(1), element(2), element(5)]),
+                Some(33),
+                33,
+            ),
+            BestCombinationSubsetDto::new(
+                3,
+                "S3",
+                BTreeSet::from([element(3), element(4), element(6)]),
+                Some(33),
+                33,
+            ),
+            BestCombinationSubsetDto::new(4, "S4", BTreeSet::from([element(5)]), Some(20), 20),
+            BestCombinationSubsetDto::new(5, "S5", BTreeSet::from([element(6)]), Some(20), 20),
+        ];
+
+        // The greedy search is lured into S1 by its ratio and has to add S4 and S5.
+        let greedy =
+            get_best_combinations(&universe, &subsets, 1, &GameWeights::default(), &limits());
+        assert_eq!(greedy[0].combined_monthly_price_cents, 80);
+        assert!(!greedy[0].proven_optimal);
+
+        let mut expected = BestCombinationDto::new(
+            vec![
+                BestCombinationPackageDto::new(2, "S2", vec![("", (2, 2))], Some(33), 33),
+                BestCombinationPackageDto::new(3, "S3", vec![("", (2, 2))], Some(33), 33),
+            ],
+            66,
+            66,
+            100,
+            0,
+        );
+        expected.proven_optimal = true;
+
+        let results = get_optimal_combinations(&universe, &subsets, 1, &limits(), false, &[]);
+        assert_eq!(results, vec![expected]);
+
+        // With a higher limit, the results are ranked by their combined price
+        let results = get_optimal_combinations(&universe, &subsets, 3, &limits(), false, &[]);
+        let ranking: Vec<(usize, usize)> = results
+            .iter()
+            .map(|r| (r.index, r.combined_monthly_price_cents))
+            .collect();
+        assert_eq!(ranking, vec![(0, 66), (1, 80), (2, 93)]);
+    }
+
+    #[test]
+    fn test_coverage_requirement_filter() {
+        dotenv::dotenv().ok();
+        let subsets = vec![
+            BestCombinationSubsetDto::new(
+                1,
+                "S1",
+                BTreeSet::from([
+                    BestCombinationElementDto::new(1, "A", 1, 0),
+                    BestCombinationElementDto::new(2, "A", 0, 1),
+                ]),
+                Some(5),
+                5,
+            ),
+            BestCombinationSubsetDto::new(
+                2,
+                "S2",
+                BTreeSet::from([BestCombinationElementDto::new(2, "A", 0, 1)]),
+                Some(5),
+                5,
+            ),
+        ];
+        let universe = BTreeSet::from([1, 2]);
+
+        let any = filter_subsets_by_coverage(subsets.clone(), CoverageRequirement::Any);
+        assert_eq!(any, subsets);
+
+        let live = filter_subsets_by_coverage(subsets.clone(), CoverageRequirement::Live);
+        assert_eq!(
+            live,
+            vec![BestCombinationSubsetDto::new(
+                1,
+                "S1",
+                BTreeSet::from([BestCombinationElementDto::new(1, "A", 1, 0)]),
+                Some(5),
+                5,
+            )]
+        );
+
+        // Only game 1 is available live, so the best combination can cover half of the universe.
+        let results =
+            get_best_combinations(&universe, &live, 1, &GameWeights::default(), &limits());
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].combined_coverage, 50);
+
+        let both = filter_subsets_by_coverage(subsets, CoverageRequirement::LiveAndHighlights);
+        assert!(both.is_empty());
+    }
+
+    #[test]
+    fn test_budgeted_combination() {
+        dotenv::dotenv().ok();
+        let subsets = vec![
+            BestCombinationSubsetDto::new(
+                1,
+                "S1",
+                BTreeSet::from([
+                    BestCombinationElementDto::new(1, "A", 1, 1),
+                    BestCombinationElementDto::new(2, "A", 1, 1),
+                    BestCombinationElementDto::new(3, "A", 1, 1),
+                ]),
+                Some(30),
+                30,
+            ),
+            BestCombinationSubsetDto::new(
+                2,
+                "S2",
+                BTreeSet::from([
+                    BestCombinationElementDto::new(1, "A", 1, 1),
+                    BestCombinationElementDto::new(2, "A", 1, 1),
+                ]),
+                Some(10),
+                10,
+            ),
+            BestCombinationSubsetDto::new(
+                3,
+                "S3",
+                BTreeSet::from([BestCombinationElementDto::new(4, "A", 1, 1)]),
+                Some(10),
+                10,
+            ),
+        ];
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+
+        let results = get_budgeted_combinations(
+            &universe,
+            &subsets,
+            2,
+            20,
+            None,
+            &[],
+            &GameWeights::default(),
+            &limits(),
+        );
+
+        assert_eq!(
+            results,
+            vec![
+                BestCombinationDto {
+                    packages: vec![
+                        BestCombinationPackageDto::new(2, "S2", vec![("A", (2, 2))], Some(10), 10),
+                        BestCombinationPackageDto::new(3, "S3", vec![("A", (2, 2))], Some(10), 10),
+                    ],
+                    combined_monthly_price_cents: 20,
+                    combined_monthly_price_yearly_subscription_in_cents: 20,
+                    combined_coverage: 75,
+                    index: 0,
+                    combined_billed_price_cents: Some(20),
+                    proven_optimal: true,
+                    timed_out: false,
+                    schedule: None,
+                    uncovered_games: Vec::new(),
+                    removed_package_ids: Vec::new(),
+                    saved_monthly_price_cents: 0,
+                    games: Vec::new(),
+                },
+                BestCombinationDto {
+                    packages: vec![BestCombinationPackageDto::new(
+                        2,
+                        "S2",
+                        vec![("A", (2, 2))],
+                        Some(10),
+                        10
+                    )],
+                    combined_monthly_price_cents: 10,
+                    combined_monthly_price_yearly_subscription_in_cents: 10,
+                    combined_coverage: 50,
+                    index: 1,
+                    combined_billed_price_cents: Some(10),
+                    proven_optimal: true,
+                    timed_out: false,
+                    schedule: None,
+                    uncovered_games: Vec::new(),
+                    removed_package_ids: Vec::new(),
+                    saved_monthly_price_cents: 0,
+                    games: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_limits_return_best_so_far() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+        let element = |game_id| BestCombinationElementDto::new(game_id, "", 1, 1);
+        let subsets = vec![
+            BestCombinationSubsetDto::new(
+                1,
+                "S1",
+                BTreeSet::from([element(1), element(2)]),
+                Some(5),
+                5,
+            ),
+            BestCombinationSubsetDto::new(2, "S2", BTreeSet::from([element(3)]), Some(5), 5),
+            BestCombinationSubsetDto::new(3, "S3", BTreeSet::from([element(4)]), Some(5), 5),
+            BestCombinationSubsetDto::new(
+                4,
+                "S4",
+                BTreeSet::from([element(3), element(4)]),
+                Some(20),
+                20,
+            ),
+        ];
+        let package_ids = |results: &[BestCombinationDto]| -> Vec<Vec<usize>> {
+            results
+                .iter()
+                .map(|r| r.packages.iter().map(|p| p.id).collect())
+                .collect()
+        };
+
+        // The first cover is found after four nodes, the search stops before the second one
+        let node_budget = SearchLimits::new(Duration::from_secs(10), 4);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            3,
+            &GameWeights::default(),
+            &node_budget,
+        );
+        assert_eq!(package_ids(&results), vec![vec![1, 2, 3]]);
+        assert!(results.iter().all(|r| r.timed_out));
+
+        // Without any cover found, the partial cover is returned as the closest one
+        let node_budget = SearchLimits::new(Duration::from_secs(10), 2);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            3,
+            &GameWeights::default(),
+            &node_budget,
+        );
+        assert_eq!(package_ids(&results), vec![vec![1, 2]]);
+        assert_eq!(results[0].combined_coverage, 75);
+        assert!(results[0].timed_out);
+
+        let results =
+            get_best_combinations(&universe, &subsets, 3, &GameWeights::default(), &limits());
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| !r.timed_out));
+    }
+
     #[test]
     fn test_large_universe() {
         dotenv::dotenv().ok();
@@ -629,7 +1785,13 @@ mod tests {
                 4,
             ),
         ];
-        let results = get_best_combinations(&universe, &subsets, limit);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
 
         assert!(
             !results.is_empty(),
@@ -786,7 +1948,13 @@ mod tests {
                 4,
             ),
         ];
-        let results = get_best_combinations(&universe, &subsets, limit);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
         dbg!(&results);
         dbg!(&expected_cover);
 
@@ -798,6 +1966,57 @@ mod tests {
         assert_eq!(results, expected_cover);
     }
 
+    #[test]
+    fn test_weighted_approximation() {
+        dotenv::dotenv().ok();
+        // Game 7 isn't offered by any package
+        let universe: BTreeSet<usize> = (1..=7).collect();
+        let subsets: Vec<BestCombinationSubsetDto> = [
+            (1, vec![1, 2, 3, 4], 40),
+            (2, vec![1, 2, 5], 33),
+            (3, vec![3, 4, 6], 33),
+            (4, vec![5], 20),
+            (5, vec![6], 20),
+        ]
+        .into_iter()
+        .map(|(id, game_ids, cost)| {
+            BestCombinationSubsetDto::new(
+                id,
+                &format!("S{}", id),
+                game_ids
+                    .into_iter()
+                    .map(|game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                    .collect(),
+                Some(cost),
+                cost,
+            )
+        })
+        .collect();
+        let package_ids = |results: &[BestCombinationDto]| -> Vec<usize> {
+            results[0].packages.iter().map(|p| p.id).collect()
+        };
+
+        let unweighted =
+            get_best_combinations(&universe, &subsets, 1, &GameWeights::default(), &limits());
+        assert_eq!(package_ids(&unweighted), vec![1, 4, 5]);
+
+        // Packages offering the prioritised games 5 and 6 are tried first
+        let weights = GameWeights::new(BTreeMap::from([(5, 3), (6, 3)]), &[5]);
+        let weighted = get_best_combinations(&universe, &subsets, 1, &weights, &limits());
+        assert_eq!(package_ids(&weighted), vec![2, 3]);
+        assert_eq!(weighted[0].combined_monthly_price_cents, 66);
+        assert_eq!(weighted[0].combined_coverage, 86);
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let parallel = pool.install(|| {
+            get_best_combinations_parallel(&universe, &subsets, 1, &weights, &limits())
+        });
+        assert_eq!(parallel, weighted);
+    }
+
     #[tokio::test]
     async fn test_get_best_combination_without_limit() {
         dotenv::dotenv().ok();
@@ -835,7 +2054,13 @@ mod tests {
 
         let limit = 1;
         let universe: BTreeSet<usize> = game_ids.iter().copied().collect();
-        let results = get_best_combinations(&universe, &subsets, limit);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
 
         assert!(!results.is_empty());
         assert_eq!(results, expected);
@@ -935,9 +2160,353 @@ mod tests {
 
         let limit = 3;
         let universe: BTreeSet<usize> = game_ids.iter().copied().collect();
-        let results = get_best_combinations(&universe, &subsets, limit);
+        let results = get_best_combinations(
+            &universe,
+            &subsets,
+            limit,
+            &GameWeights::default(),
+            &limits(),
+        );
 
         assert!(!results.is_empty());
         assert_eq!(results, expected);
     }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        dotenv::dotenv().ok();
+        let universe: BTreeSet<usize> = (1..=30).collect();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        // Pseudo-random instances, including duplicated packages which yield duplicate combinations
+        let mut state: u64 = 7;
+        let mut next = |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % bound) as usize
+        };
+
+        for _ in 0..10 {
+            let mut subsets: Vec<BestCombinationSubsetDto> = (1..=15)
+                .map(|id| {
+                    let cost = (next(4) + 1) * 5;
+                    BestCombinationSubsetDto::new(
+                        id,
+                        &format!("S{}", id),
+                        (0..6)
+                            .map(|_| BestCombinationElementDto::new(next(30) + 1, "", 1, 1))
+                            .collect(),
+                        Some(cost),
+                        cost,
+                    )
+                })
+                .collect();
+            subsets.push(subsets[0].clone());
+
+            for limit in [1, 2, 3, 4, 5, 6, 10] {
+                let sequential = get_best_combinations(
+                    &universe,
+                    &subsets,
+                    limit,
+                    &GameWeights::default(),
+                    &limits(),
+                );
+                let parallel = pool.install(|| {
+                    get_best_combinations_parallel(
+                        &universe,
+                        &subsets,
+                        limit,
+                        &GameWeights::default(),
+                        &limits(),
+                    )
+                });
+                assert_eq!(parallel, sequential, "limit {}", limit);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cheapest_first_combination() {
+        dotenv::dotenv().ok();
+        let universe = BTreeSet::from([1, 2, 3, 4]);
+        let subsets: Vec<BestCombinationSubsetDto> = [
+            (1, vec![1, 2, 3], 30),
+            (2, vec![4], 50),
+            (3, vec![1, 2], 21),
+            (4, vec![3, 4], 21),
+        ]
+        .into_iter()
+        .map(|(id, game_ids, cost)| {
+            BestCombinationSubsetDto::new(
+                id,
+                &format!("S{}", id),
+                game_ids
+                    .into_iter()
+                    .map(|game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                    .collect(),
+                Some(cost),
+                cost,
+            )
+        })
+        .collect();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        // The search follows S1, the cheapest package per game, into a cover of 51
+        let sequential =
+            get_best_combinations(&universe, &subsets, 1, &GameWeights::default(), &limits());
+        assert_eq!(sequential[0].combined_monthly_price_cents, 51);
+
+        // Starting from S3 instead leads to a cover of 42
+        for parallel in [false, true] {
+            let cheapest = pool.install(|| {
+                get_cheapest_first_combination(
+                    &universe,
+                    &subsets,
+                    &GameWeights::default(),
+                    &limits(),
+                    parallel,
+                )
+            });
+            assert_eq!(cheapest.len(), 1);
+            let package_ids: Vec<usize> = cheapest[0].packages.iter().map(|p| p.id).collect();
+            assert_eq!(package_ids, vec![3, 4], "parallel: {}", parallel);
+            assert_eq!(cheapest[0].combined_monthly_price_cents, 42);
+        }
+    }
+
+    #[test]
+    fn test_seed_combinations() {
+        dotenv::dotenv().ok();
+        let universe: BTreeSet<usize> = (1..=6).collect();
+        let subsets: Vec<BestCombinationSubsetDto> = [
+            (1, vec![1, 2, 3, 4], 40),
+            (2, vec![1, 2, 5], 33),
+            (3, vec![3, 4, 6], 33),
+            (4, vec![5], 20),
+            (5, vec![6], 20),
+        ]
+        .into_iter()
+        .map(|(id, game_ids, cost)| {
+            BestCombinationSubsetDto::new(
+                id,
+                &format!("S{}", id),
+                game_ids
+                    .into_iter()
+                    .map(|game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                    .collect(),
+                Some(cost),
+                cost,
+            )
+        })
+        .collect();
+        let weights = GameWeights::default();
+        let package_ids = |result: &BestCombinationDto| -> Vec<usize> {
+            result.packages.iter().map(|p| p.id).collect()
+        };
+
+        let cold = get_best_combinations(&universe, &subsets, 2, &weights, &limits());
+        assert_eq!(package_ids(&cold[0]), vec![1, 4, 5]);
+
+        // S9 isn't offered anymore, so the cover is repaired with S3 instead of S1
+        for warm_start in [vec![2, 3], vec![2, 9]] {
+            let seeded =
+                seed_combinations(&universe, &subsets, 2, &weights, cold.clone(), &warm_start);
+            assert_eq!(seeded.len(), 2);
+            assert_eq!(package_ids(&seeded[0]), vec![2, 3]);
+            assert_eq!(seeded[0].combined_monthly_price_cents, 66);
+            assert_eq!(seeded[1].packages, cold[0].packages);
+            assert_eq!(seeded[1].index, 1);
+        }
+
+        // A cover which isn't cheaper leaves the combinations of the search as they are
+        let seeded = seed_combinations(&universe, &subsets, 2, &weights, cold.clone(), &[1, 5]);
+        assert_eq!(seeded, cold);
+    }
+
+    /// Games 1 to 3 with a package covering all of them, and a cheaper one covering only game 1 which
+    /// is required by name, so it is redundant in the cheapest combination containing it.
+    fn required_package_fixture() -> (BTreeSet<usize>, Vec<BestCombinationSubsetDto>, Vec<usize>) {
+        let element = |game_id| BestCombinationElementDto::new(game_id, "", 1, 1);
+        let subsets = vec![
+            BestCombinationSubsetDto::new(
+                1,
+                "Full",
+                BTreeSet::from([element(1), element(2), element(3)]),
+                Some(10),
+                10,
+            ),
+            BestCombinationSubsetDto::new(2, "Sky Sport", BTreeSet::from([element(1)]), Some(8), 8),
+            BestCombinationSubsetDto::new(
+                3,
+                "Partial",
+                BTreeSet::from([element(2), element(3)]),
+                Some(15),
+                15,
+            ),
+        ];
+        let package_filter = PackageFilter {
+            required_name_patterns: vec!["sky".to_string()],
+            ..PackageFilter::default()
+        };
+        let forced = forced_package_ids(&subsets, &[], &package_filter);
+
+        (BTreeSet::from([1, 2, 3]), subsets, forced)
+    }
+
+    /// Strips the combinations like the worker does and returns their package IDs.
+    fn stripped_package_ids(
+        combinations: Vec<BestCombinationDto>,
+        subsets: &[BestCombinationSubsetDto],
+        universe: &BTreeSet<usize>,
+        forced: &[usize],
+    ) -> Vec<Vec<usize>> {
+        let (combinations, _) =
+            redundancy::strip_redundant_packages(combinations, subsets, universe, forced);
+        combinations
+            .iter()
+            .map(|c| c.packages.iter().map(|p| p.id).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_forced_package_ids() {
+        let (_, subsets, forced) = required_package_fixture();
+        assert_eq!(forced, vec![2]);
+
+        let forced = forced_package_ids(&subsets, &[3, 4], &PackageFilter::default());
+        assert_eq!(forced, vec![3]);
+    }
+
+    #[test]
+    fn test_required_package_survives_covering_solvers() {
+        dotenv::dotenv().ok();
+        let (universe, subsets, forced) = required_package_fixture();
+
+        for exact in [false, true] {
+            let reduced = reduction::reduce(&universe, &subsets, &forced, true);
+            let combinations = if exact {
+                get_optimal_combinations(
+                    &reduced.universe,
+                    &reduced.subsets,
+                    1,
+                    &limits(),
+                    false,
+                    &[],
+                )
+            } else {
+                get_best_combinations(
+                    &reduced.universe,
+                    &reduced.subsets,
+                    1,
+                    &GameWeights::default(),
+                    &limits(),
+                )
+            };
+            let combinations = reduced.expand(combinations, &subsets, &universe);
+
+            assert_eq!(
+                stripped_package_ids(combinations, &subsets, &universe, &forced),
+                vec![vec![1, 2]],
+                "exact: {}",
+                exact
+            );
+        }
+    }
+
+    #[test]
+    fn test_required_package_survives_budget() {
+        dotenv::dotenv().ok();
+        let (universe, subsets, forced) = required_package_fixture();
+
+        let combinations = get_budgeted_combinations(
+            &universe,
+            &subsets,
+            1,
+            20,
+            None,
+            &forced,
+            &GameWeights::default(),
+            &limits(),
+        );
+
+        assert_eq!(
+            stripped_package_ids(combinations, &subsets, &universe, &forced),
+            vec![vec![1, 2]]
+        );
+    }
+
+    #[test]
+    fn test_required_package_survives_max_packages() {
+        dotenv::dotenv().ok();
+        let (universe, subsets, forced) = required_package_fixture();
+
+        let combinations = get_budgeted_combinations(
+            &universe,
+            &subsets,
+            1,
+            usize::MAX,
+            Some(1),
+            &forced,
+            &GameWeights::default(),
+            &limits(),
+        );
+
+        // The required package takes the only slot, although another one covers all games
+        assert_eq!(
+            stripped_package_ids(combinations, &subsets, &universe, &forced),
+            vec![vec![2]]
+        );
+    }
+
+    #[test]
+    fn test_required_package_survives_pareto() {
+        dotenv::dotenv().ok();
+        let (universe, subsets, forced) = required_package_fixture();
+
+        let combinations = get_pareto_combinations(
+            &universe,
+            &subsets,
+            usize::MAX,
+            None,
+            &forced,
+            &GameWeights::default(),
+            &limits(),
+        );
+
+        assert_eq!(
+            stripped_package_ids(combinations, &subsets, &universe, &forced),
+            vec![vec![2], vec![1, 2]]
+        );
+    }
+
+    #[test]
+    fn test_required_package_survives_scheduled() {
+        dotenv::dotenv().ok();
+        let (universe, subsets, forced) = required_package_fixture();
+        let games: Vec<GameSchema> = universe
+            .iter()
+            .map(|&game_id| GameSchema {
+                id: oid::ObjectId::new(),
+                game_id: game_id as u32,
+                team_away: "TEAM A".to_string(),
+                team_home: "TEAM B".to_string(),
+                starts_at: format!("2024-0{}-01 20:30:00", game_id + 7),
+                tournament_name: "TOURNAMENT X".to_string(),
+            })
+            .collect();
+
+        let combinations =
+            get_scheduled_combinations(&universe, &subsets, &games, &forced, &limits());
+
+        let schedule = combinations[0].schedule.as_ref().unwrap();
+        assert!(schedule.months.iter().all(|m| m.package_ids.contains(&2)));
+        assert!(schedule.yearly_package_ids.contains(&2));
+    }
 }
@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use libs::{
+    models::dtos::{BestCombinationDto, BestCombinationSubsetDto},
+    solver::{SearchLimits, SetCoverSolver, SolverRegistry},
+};
+
+use super::{service, weights::GameWeights};
+
+/// The classic greedy heuristic, which repeatedly books the package with the lowest price per newly
+/// covered game. It returns a single combination, the first one of the [`RecursiveSolver`].
+pub struct GreedySolver;
+
+impl SetCoverSolver for GreedySolver {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn solve(
+        &self,
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        _limit: usize,
+        weights: &BTreeMap<usize, usize>,
+        limits: &SearchLimits,
+    ) -> Vec<BestCombinationDto> {
+        let weights = GameWeights::new(weights.clone(), &[]);
+        service::get_best_combinations(universe, subsets, 1, &weights, limits)
+    }
+
+    fn supports_warm_start(&self) -> bool {
+        true
+    }
+
+    /// Keeps the repaired warm start if it is cheaper (see [`service::seed_combinations`]).
+    fn solve_warm(
+        &self,
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        limit: usize,
+        weights: &BTreeMap<usize, usize>,
+        limits: &SearchLimits,
+        warm_start: &[usize],
+    ) -> Vec<BestCombinationDto> {
+        let combinations = self.solve(universe, subsets, limit, weights, limits);
+        let weights = GameWeights::new(weights.clone(), &[]);
+        service::seed_combinations(universe, subsets, 1, &weights, combinations, warm_start)
+    }
+}
+
+/// The ratio-guided recursive backtracking search (see [`service::get_best_combinations`]).
+pub struct RecursiveSolver {
+    pub parallel: bool,
+}
+
+impl SetCoverSolver for RecursiveSolver {
+    fn name(&self) -> &'static str {
+        "recursive"
+    }
+
+    fn solve(
+        &self,
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        limit: usize,
+        weights: &BTreeMap<usize, usize>,
+        limits: &SearchLimits,
+    ) -> Vec<BestCombinationDto> {
+        let weights = GameWeights::new(weights.clone(), &[]);
+        if self.parallel {
+            service::get_best_combinations_parallel(universe, subsets, limit, &weights, limits)
+        } else {
+            service::get_best_combinations(universe, subsets, limit, &weights, limits)
+        }
+    }
+
+    fn supports_warm_start(&self) -> bool {
+        true
+    }
+
+    /// Keeps the repaired warm start if it is cheaper (see [`service::seed_combinations`]).
+    fn solve_warm(
+        &self,
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        limit: usize,
+        weights: &BTreeMap<usize, usize>,
+        limits: &SearchLimits,
+        warm_start: &[usize],
+    ) -> Vec<BestCombinationDto> {
+        let combinations = self.solve(universe, subsets, limit, weights, limits);
+        let weights = GameWeights::new(weights.clone(), &[]);
+        service::seed_combinations(universe, subsets, limit, &weights, combinations, warm_start)
+    }
+}
+
+/// The recursive search started from every top-level branch, returning the cheapest first cover
+/// (see [`service::get_cheapest_first_combination`]). It returns a single combination.
+pub struct CheapestFirstSolver {
+    pub parallel: bool,
+}
+
+impl SetCoverSolver for CheapestFirstSolver {
+    fn name(&self) -> &'static str {
+        "cheapest_first"
+    }
+
+    fn solve(
+        &self,
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        _limit: usize,
+        weights: &BTreeMap<usize, usize>,
+        limits: &SearchLimits,
+    ) -> Vec<BestCombinationDto> {
+        let weights = GameWeights::new(weights.clone(), &[]);
+        service::get_cheapest_first_combination(universe, subsets, &weights, limits, self.parallel)
+    }
+}
+
+/// The exact branch-and-bound search (see [`service::get_optimal_combinations`]).
+///
+/// It covers every offered game at the lowest price, which satisfies all must-covers, so the game
+/// weights can't change its result and are ignored.
+pub struct OptimalSolver {
+    pub parallel: bool,
+}
+
+impl SetCoverSolver for OptimalSolver {
+    fn name(&self) -> &'static str {
+        "exact"
+    }
+
+    fn solve(
+        &self,
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        limit: usize,
+        _weights: &BTreeMap<usize, usize>,
+        limits: &SearchLimits,
+    ) -> Vec<BestCombinationDto> {
+        service::get_optimal_combinations(universe, subsets, limit, limits, self.parallel, &[])
+    }
+
+    fn supports_warm_start(&self) -> bool {
+        true
+    }
+
+    fn supports_reductions(&self) -> bool {
+        true
+    }
+
+    fn solve_warm(
+        &self,
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        limit: usize,
+        _weights: &BTreeMap<usize, usize>,
+        limits: &SearchLimits,
+        warm_start: &[usize],
+    ) -> Vec<BestCombinationDto> {
+        service::get_optimal_combinations(
+            universe,
+            subsets,
+            limit,
+            limits,
+            self.parallel,
+            warm_start,
+        )
+    }
+}
+
+/// Registers all set cover solvers of the worker.
+///
+/// The budgeted and the scheduled searches take further inputs than a universe and its subsets, so
+/// they are run by the processor directly instead.
+///
+/// # Arguments
+///
+/// * `parallel` - Whether the solvers search on the rayon thread pool, where supported.
+///
+pub fn solver_registry(parallel: bool) -> SolverRegistry {
+    SolverRegistry::new()
+        .register(GreedySolver)
+        .register(RecursiveSolver { parallel })
+        .register(CheapestFirstSolver { parallel })
+        .register(OptimalSolver { parallel })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use libs::models::dtos::BestCombinationElementDto;
+
+    #[test]
+    fn test_registered_solvers_cover_universe() {
+        dotenv::dotenv().ok();
+        let universe: BTreeSet<usize> = (1..=6).collect();
+        let subsets: Vec<BestCombinationSubsetDto> = [
+            (1, vec![1, 2, 3, 4], 40),
+            (2, vec![1, 2, 5], 33),
+            (3, vec![3, 4, 6], 33),
+            (4, vec![5], 20),
+            (5, vec![6], 20),
+        ]
+        .into_iter()
+        .map(|(id, game_ids, cost)| {
+            BestCombinationSubsetDto::new(
+                id,
+                &format!("S{}", id),
+                game_ids
+                    .into_iter()
+                    .map(|game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                    .collect(),
+                Some(cost),
+                cost,
+            )
+        })
+        .collect();
+
+        for parallel in [false, true] {
+            let registry = solver_registry(parallel);
+            assert_eq!(
+                registry.names(),
+                vec!["greedy", "recursive", "cheapest_first", "exact"]
+            );
+
+            for solver in registry.iter() {
+                let limits = SearchLimits::new(Duration::from_secs(10), usize::MAX);
+                let results = solver.solve(&universe, &subsets, 3, &BTreeMap::new(), &limits);
+
+                assert!(!results.is_empty(), "{}", solver.name());
+                assert!(results.len() <= 3, "{}", solver.name());
+                assert!(
+                    results.iter().all(|r| r.combined_coverage == 100),
+                    "{}",
+                    solver.name()
+                );
+            }
+        }
+
+        let registry = solver_registry(false);
+        let limits = SearchLimits::new(Duration::from_secs(10), usize::MAX);
+        let greedy = registry.get("greedy").unwrap();
+        let exact = registry.get("exact").unwrap();
+        assert_eq!(
+            greedy
+                .solve(&universe, &subsets, 3, &BTreeMap::new(), &limits)
+                .len(),
+            1
+        );
+        assert_eq!(
+            exact.solve(&universe, &subsets, 1, &BTreeMap::new(), &limits)[0]
+                .combined_monthly_price_cents,
+            66
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use libs::models::dtos::BestCombinationSubsetDto;
+
+/// The per-game weights and must-cover constraints of a request.
+///
+/// Games without an explicit weight weigh `1`, so the default represents the unweighted problem.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GameWeights {
+    weights: BTreeMap<usize, usize>,
+    must_cover: BTreeSet<usize>,
+}
+
+impl GameWeights {
+    pub fn new(weights: BTreeMap<usize, usize>, must_cover: &[usize]) -> Self {
+        GameWeights {
+            weights,
+            must_cover: must_cover.iter().copied().collect(),
+        }
+    }
+
+    pub fn weight(&self, game_id: usize) -> usize {
+        self.weights.get(&game_id).copied().unwrap_or(1)
+    }
+
+    pub fn is_must_cover(&self, game_id: usize) -> bool {
+        self.must_cover.contains(&game_id)
+    }
+
+    /// Removes the must-cover constraints of games which aren't offered by any of the `subsets`,
+    /// as no combination could ever satisfy them.
+    ///
+    /// # Returns
+    ///
+    /// The IDs of the games whose constraint has been removed.
+    pub fn drop_unavailable_must_covers(
+        &mut self,
+        subsets: &[BestCombinationSubsetDto],
+    ) -> Vec<usize> {
+        let offered: BTreeSet<usize> = subsets.iter().flat_map(|s| s.element_ids()).collect();
+        let unavailable: Vec<usize> = self.must_cover.difference(&offered).copied().collect();
+
+        for game_id in &unavailable {
+            self.must_cover.remove(game_id);
+        }
+
+        unavailable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libs::models::dtos::BestCombinationElementDto;
+
+    #[test]
+    fn test_drop_unavailable_must_covers() {
+        let subsets = vec![BestCombinationSubsetDto::new(
+            1,
+            "S1",
+            BTreeSet::from([BestCombinationElementDto::new(1, "A", 1, 1)]),
+            Some(5),
+            5,
+        )];
+        let mut weights = GameWeights::new(BTreeMap::from([(1, 3)]), &[1, 2]);
+
+        assert_eq!(weights.drop_unavailable_must_covers(&subsets), vec![2]);
+        assert!(weights.is_must_cover(1));
+        assert!(!weights.is_must_cover(2));
+        assert_eq!(weights.weight(1), 3);
+        assert_eq!(weights.weight(2), 1);
+    }
+}
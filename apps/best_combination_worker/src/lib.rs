@@ -2,4 +2,4 @@ mod config;
 mod core;
 
 pub use config::CONFIG;
-pub use core::Processor;
+pub use core::{solver_registry, Processor};
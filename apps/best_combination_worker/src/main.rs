@@ -1,13 +1,20 @@
 use std::sync::Arc;
 
+use actix_web::{
+    web::{self, Data},
+    App, HttpServer,
+};
 use tokio::signal;
 
 use best_combination_worker::{Processor, CONFIG};
 use libs::{
     caching,
-    constants::{DATABASE_NAME, STREAMING_PACKAGE_COLLECTION_NAME},
-    db::{dao::StreamingPackageDao, DocumentDatabaseConnector, MongoClient},
-    logging,
+    constants::{DATABASE_NAME, GAME_COLLECTION_NAME, STREAMING_PACKAGE_COLLECTION_NAME},
+    db::{
+        dao::{GameDao, StreamingPackageDao},
+        DocumentDatabaseConnector, MongoClient,
+    },
+    logging, metrics,
 };
 
 #[tokio::main]
@@ -16,12 +23,35 @@ async fn main() -> anyhow::Result<()> {
 
     logging::init_logging();
 
+    if CONFIG.solver_threads > 1 {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(CONFIG.solver_threads)
+            .build_global()?;
+        log::info!("Running the solvers on {} threads", CONFIG.solver_threads);
+    }
+
+    // The worker has no API, so its metrics are served on their own for Prometheus to scrape
+    let registry = metrics::init_metrics();
+    let metrics_server = HttpServer::new(move || {
+        App::new()
+            .app_data(Data::new(registry.clone()))
+            .route("/metrics", web::get().to(metrics::metrics_handler))
+    })
+    .bind(format!("0.0.0.0:{}", CONFIG.worker_metrics_port))?
+    .run();
+    tokio::spawn(metrics_server);
+
     let redis_client = caching::init_redis(&CONFIG.redis_url).await?;
     let mongo_client = MongoClient::init(&CONFIG.mongodb_uri, DATABASE_NAME).await;
     let package_dao =
         StreamingPackageDao::new(mongo_client.get_collection(STREAMING_PACKAGE_COLLECTION_NAME));
+    let game_dao = GameDao::new(mongo_client.get_collection(GAME_COLLECTION_NAME));
 
-    let processor = Processor::new(Arc::new(redis_client), Arc::new(package_dao));
+    let processor = Processor::new(
+        Arc::new(redis_client),
+        Arc::new(package_dao),
+        Arc::new(game_dao),
+    );
     processor.start().await?;
 
     let processor_handle = tokio::spawn(async move {
@@ -5,7 +5,7 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use libs::{
     constants::{DATABASE_NAME, STREAMING_PACKAGE_COLLECTION_NAME},
     db::{dao::StreamingPackageDao, DocumentDatabaseConnector, MongoClient},
-    models::dtos::BestCombinationSubsetDto,
+    models::{dtos::BestCombinationSubsetDto, fetch_types::PackageFilter},
 };
 
 async fn init_mongo_client() -> MongoClient {
@@ -19,7 +19,7 @@ async fn preprocess_aggregation(
     ids: &[usize],
 ) -> Vec<BestCombinationSubsetDto> {
     package_dao
-        .aggregate_subsets_by_game_ids(ids)
+        .aggregate_subsets_by_game_ids(ids, &PackageFilter::default())
         .await
         .unwrap()
 }
@@ -15,13 +15,33 @@ impl CompositeKey {
     pub fn new(ids: Vec<usize>, opts: FetchOptions) -> CompositeKey {
         CompositeKey { ids, opts }
     }
+
+    /// Produces a hash of the options only, which the keys of all requests with the same options
+    /// share, no matter their games.
+    pub fn options_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.opts.hash(&mut hasher);
+        hasher.finish()
+    }
 }
 
 impl From<TaskMessagePayload> for CompositeKey {
     fn from(o: TaskMessagePayload) -> Self {
         CompositeKey {
             ids: o.game_ids,
-            opts: FetchOptions { limit: o.limit },
+            opts: FetchOptions {
+                limit: o.limit,
+                mode: o.mode,
+                coverage: o.coverage,
+                max_monthly_price_cents: o.max_monthly_price_cents,
+                max_packages: o.max_packages,
+                priorities: o.priorities,
+                pricing: o.pricing,
+                solver: o.solver,
+                owned_package_ids: o.owned_package_ids,
+                package_filter: o.package_filter,
+                include_game_coverage: o.include_game_coverage,
+            },
         }
     }
 }
@@ -39,3 +59,80 @@ impl StableHash for CompositeKey {
         hasher.finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fetch_types::CoverageRequirement;
+
+    #[test]
+    fn test_stable_hash_ignores_id_order() {
+        let a = CompositeKey::new(vec![3, 1, 2], FetchOptions::new(1));
+        let b = CompositeKey::new(vec![1, 2, 3], FetchOptions::new(1));
+
+        assert_eq!(a.stable_hash(), b.stable_hash());
+    }
+
+    #[test]
+    fn test_stable_hash_differs_by_coverage_requirement() {
+        let any = CompositeKey::new(vec![1, 2, 3], FetchOptions::new(1));
+        let live = CompositeKey::new(
+            vec![1, 2, 3],
+            FetchOptions {
+                coverage: CoverageRequirement::Live,
+                ..FetchOptions::new(1)
+            },
+        );
+
+        assert_ne!(any.stable_hash(), live.stable_hash());
+    }
+
+    #[test]
+    fn test_options_hash_ignores_ids() {
+        let a = CompositeKey::new(vec![1, 2, 3], FetchOptions::new(1));
+        let b = CompositeKey::new(vec![1, 2, 4], FetchOptions::new(1));
+        let c = CompositeKey::new(vec![1, 2, 3], FetchOptions::new(2));
+
+        assert_ne!(a.stable_hash(), b.stable_hash());
+        assert_eq!(a.options_hash(), b.options_hash());
+        assert_ne!(a.options_hash(), c.options_hash());
+    }
+
+    #[test]
+    fn test_game_coverage_is_part_of_the_key() {
+        let key = CompositeKey::new(
+            vec![1, 2, 3],
+            FetchOptions {
+                include_game_coverage: true,
+                ..FetchOptions::new(1)
+            },
+        );
+        let payload = TaskMessagePayload::from(key.clone());
+
+        assert!(payload.include_game_coverage);
+        assert_eq!(CompositeKey::from(payload), key);
+        assert_ne!(
+            key.stable_hash(),
+            CompositeKey::new(vec![1, 2, 3], FetchOptions::new(1)).stable_hash()
+        );
+    }
+
+    #[test]
+    fn test_stable_hash_differs_by_owned_packages() {
+        let owned = |ids: Vec<usize>| {
+            CompositeKey::new(
+                vec![1, 2, 3],
+                FetchOptions {
+                    owned_package_ids: ids,
+                    ..FetchOptions::new(1)
+                },
+            )
+        };
+
+        assert_ne!(owned(vec![]).stable_hash(), owned(vec![4]).stable_hash());
+        assert_eq!(
+            owned(vec![4, 5]).stable_hash(),
+            owned(vec![5, 4]).stable_hash()
+        );
+    }
+}
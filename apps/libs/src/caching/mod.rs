@@ -1,9 +1,13 @@
 mod client;
 mod composite_key;
 mod hash;
+mod updates;
 mod utils;
+mod warm_start;
 
 pub use client::{init_redis, RedisClient};
 pub use composite_key::CompositeKey;
 pub use hash::{hash_key, StableHash};
+pub use updates::subscribe_updates;
 pub use utils::{cache_entry, get_cached_entry, CacheEntry, CacheValue};
+pub use warm_start::{find_warm_start, record_warm_start, WarmStart};
@@ -0,0 +1,49 @@
+use anyhow::Context;
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
+
+use super::{hash_key, CacheEntry, StableHash};
+
+/// Creates the name of the Redis pub/sub channel the updates of the entry of `key` are published
+/// to.
+pub(super) fn update_channel<K: StableHash>(key: &K) -> String {
+    format!("updates:{}", hash_key(key))
+}
+
+/// Subscribes to the updates of a cached entry.
+///
+/// Every entry written with [`cache_entry`](super::cache_entry) is also published to a channel of
+/// its key, so the transitions of a job can be followed without polling the cache. Only updates
+/// published after the subscription has been established are received, so the current entry
+/// should be read after subscribing to not miss a transition.
+///
+/// # Arguments
+///
+/// * `redis_client` - A reference to the Redis client used to connect to the Redis server.
+/// * `key` - A reference to the key of the entry to follow.
+///
+/// # Returns
+///
+/// A stream of the entries published for the key. Updates which can't be deserialized are
+/// yielded as errors.
+///
+/// # Errors
+///
+/// This function returns an error if the connection to Redis fails or the channel can't be
+/// subscribed to.
+pub async fn subscribe_updates<K, T>(
+    redis_client: &redis::Client,
+    key: &K,
+) -> anyhow::Result<impl Stream<Item = anyhow::Result<CacheEntry<K, T>>>>
+where
+    K: StableHash + for<'de> Deserialize<'de>,
+    T: for<'de> Deserialize<'de>,
+{
+    let mut pubsub = redis_client.get_async_pubsub().await?;
+    pubsub.subscribe(update_channel(key)).await?;
+
+    Ok(pubsub.into_on_message().map(|msg| {
+        let payload: String = msg.get_payload()?;
+        serde_json::from_str(&payload).context("Failed to deserialize cache update")
+    }))
+}
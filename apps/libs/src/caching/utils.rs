@@ -2,29 +2,66 @@ use anyhow::Context;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 
-use super::{hash_key, StableHash};
+use super::{hash_key, updates::update_channel, StableHash};
 
 /// Time-To-Live (TTL) for cache entries in seconds.
 /// Preset to 1 Week: 7 days * 24 hours * 60 minutes * 60 seconds
-const CACHE_TTL: u64 = 7 * 24 * 60 * 60;
+pub(super) const CACHE_TTL: u64 = 7 * 24 * 60 * 60;
 
-/// Represents the value stored in the cache.
+/// TTL for the entries of running jobs in seconds.
+/// A running job refreshes its entry with every progress update, so the entry of a job whose
+/// worker died expires within a minute and the request can be enqueued again.
+const RUNNING_TTL: u64 = 60;
+
+/// TTL for the entries of failed jobs in seconds.
+/// Preset to 5 minutes, so the failure can be reported before the request is enqueued again.
+const FAILED_TTL: u64 = 5 * 60;
+
+/// Represents the value stored in the cache, which tracks the lifecycle of a job.
 ///
 /// This enum enables distinguishing between values that are still being
 /// computed and values that are fully computed and ready to be served.
 ///
 /// # Variants
 ///
-/// * `Processing` - Indicates that the requested data is not yet available
-///    and is currently being computed or fetched in the background.
+/// * `Queued` - Indicates that the job has been enqueued, but no worker has picked it up yet.
 ///    Clients may need to try again later.
 ///
-/// * `Data(T)` - Holds the actual cached value of type `T`.
+/// * `Running` - Indicates that a worker is computing the requested data. `explored_nodes` is the
+///   number of search nodes explored so far, and `best_cost_so_far` the billed cost in cents of
+///   the cheapest cover found so far, if the search has found one yet.
+///
+/// * `Completed(T)` - Holds the actual cached value of type `T`.
+///
+/// * `Failed` - Indicates that the job failed, with the `reason` of the failure.
+///
+/// Entries written before the lifecycle was tracked used `Processing` and `Data(T)`, which are
+/// still read as `Queued` and `Completed(T)` until they expire.
 ///
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub enum CacheValue<T> {
-    Processing,
-    Data(T),
+    #[serde(alias = "Processing")]
+    Queued,
+    Running {
+        explored_nodes: usize,
+        best_cost_so_far: Option<usize>,
+    },
+    #[serde(alias = "Data")]
+    Completed(T),
+    Failed {
+        reason: String,
+    },
+}
+
+impl<T> CacheValue<T> {
+    /// Returns the time-to-live of the value in seconds.
+    fn ttl(&self) -> u64 {
+        match self {
+            CacheValue::Running { .. } => RUNNING_TTL,
+            CacheValue::Failed { .. } => FAILED_TTL,
+            CacheValue::Queued | CacheValue::Completed(_) => CACHE_TTL,
+        }
+    }
 }
 
 /// A generic cache entry structure for storing key-value pairs in Redis.
@@ -33,10 +70,11 @@ pub enum CacheValue<T> {
 ///
 /// The `CacheEntry` pairs a unique key (of type `K`) with a `CacheValue` (of type `T`), which
 /// can either be:
-/// - `Processing` if the data is not yet computed or fetched, or
-/// - `Data(T)` if the requested data is available.
+/// - `Queued` or `Running` if the data is not yet computed or fetched,
+/// - `Completed(T)` if the requested data is available, or
+/// - `Failed` if computing the data failed.
 ///
-/// This allows the cache to represent both pending and ready states in a single entry.
+/// This allows the cache to represent the whole lifecycle of a job in a single entry.
 ///
 /// # Type Parameters
 ///
@@ -52,12 +90,12 @@ pub enum CacheValue<T> {
 /// // An example cache entry storing a string result
 /// let entry_in_progress: CacheEntry<Vec<usize>, String> = CacheEntry {
 ///     key: vec![1, 2, 3],
-///     value: CacheValue::Processing,
+///     value: CacheValue::Queued,
 /// };
 ///
 /// let entry_ready: CacheEntry<Vec<usize>, String> = CacheEntry {
 ///     key: vec![1, 2, 3],
-///     value: CacheValue::Data("Cached result".to_string()),
+///     value: CacheValue::Completed("Cached result".to_string()),
 /// };
 /// ```
 #[derive(Serialize, Deserialize, Debug)]
@@ -76,19 +114,23 @@ pub struct CacheEntry<K, T> {
 /// Internally, `cache_entry` calls [`hash_key`](fn.hash_key.html) to turn the key into a
 /// unique string via the `StableHash` trait. It then serializes the entire
 /// [`CacheEntry`](struct.CacheEntry.html) and stores it under that key with a preset TTL of 1 week.
+/// The entries of running and failed jobs expire much sooner, see [`CacheValue`].
+///
+/// The entry is also published to the update channel of its key, so subscribers of
+/// [`subscribe_updates`](super::subscribe_updates) are notified of every transition of a job.
 ///
 /// # Arguments
 ///
 /// * `redis_client` - A reference to the Redis client used to connect to the Redis server.
 /// * `key` - A reference to a type that implements `StableHash` and `Serialize`.
-/// * `value` - The [`CacheValue`](enum.CacheValue.html) to store (either `Processing` or `Data(T)`).
+/// * `value` - The [`CacheValue`](enum.CacheValue.html) to store (e.g. `Queued` or `Completed(T)`).
 ///
 /// # Errors
 ///
 /// This function returns an error if:
 /// - It fails to establish a connection with Redis.
 /// - The value cannot be serialized into JSON.
-/// - The `SET` operation on Redis fails.
+/// - The `SET` or `PUBLISH` operation on Redis fails.
 pub async fn cache_entry<K, T>(
     redis_client: &redis::Client,
     key: &K,
@@ -101,10 +143,18 @@ where
     let mut connection = redis_client.get_multiplexed_tokio_connection().await?;
 
     let cache_key = hash_key(key);
+    let ttl = value.ttl();
     let cache_value = serde_json::to_string(&CacheEntry { key, value })
         .context("Failed to serialize cache value")?;
 
-    let _: () = connection.set_ex(cache_key, cache_value, CACHE_TTL).await?;
+    let _: () = redis::pipe()
+        .atomic()
+        .set_ex(cache_key, &cache_value, ttl)
+        .ignore()
+        .publish(update_channel(key), &cache_value)
+        .ignore()
+        .query_async(&mut connection)
+        .await?;
     Ok(())
 }
 
@@ -153,3 +203,22 @@ where
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_legacy_values() {
+        let processing: CacheEntry<Vec<usize>, String> =
+            serde_json::from_str(r#"{"key":[1,2],"value":"Processing"}"#).unwrap();
+        assert_eq!(processing.value, CacheValue::Queued);
+
+        let data: CacheEntry<Vec<usize>, String> =
+            serde_json::from_str(r#"{"key":[1,2],"value":{"Data":"Cached result"}}"#).unwrap();
+        assert_eq!(
+            data.value,
+            CacheValue::Completed("Cached result".to_string())
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::Context;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+
+use super::{utils::CACHE_TTL, CompositeKey};
+
+/// Number of recent solutions kept per set of fetch options.
+const WARM_START_CAPACITY: isize = 16;
+
+/// The cover of a solved request, kept to warm-start the solvers on similar requests.
+///
+/// Requests which only differ by a few games, e.g. after adding or removing a team, usually have
+/// similar covers. The cover of one can therefore seed the search of the other as an initial
+/// incumbent, which only has to be repaired for the changed games.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WarmStart {
+    pub game_ids: Vec<usize>,
+    pub package_ids: Vec<usize>,
+}
+
+impl WarmStart {
+    /// Returns the Jaccard distance between the games of the solved request and `game_ids`, i.e.
+    /// the share of games of both requests which only one of them contains. Identical sets of
+    /// games have a distance of `0.0`, disjoint ones a distance of `1.0`.
+    pub fn distance(&self, game_ids: &[usize]) -> f64 {
+        let shared = self
+            .game_ids
+            .iter()
+            .filter(|id| game_ids.contains(id))
+            .count();
+        let union = self.game_ids.len() + game_ids.len() - shared;
+
+        if union == 0 {
+            0.0
+        } else {
+            (union - shared) as f64 / union as f64
+        }
+    }
+}
+
+/// Creates the Redis key of the recent solutions of all requests with the options of `key`.
+fn warm_start_key(key: &CompositeKey) -> String {
+    format!("warm:{}", key.options_hash())
+}
+
+/// Records the cover of a solved request for warm starts.
+///
+/// The most recent solutions of each set of fetch options are kept in a Redis list, which expires
+/// like the cache entries.
+///
+/// # Arguments
+///
+/// * `redis_client` - A reference to the Redis client used to connect to the Redis server.
+/// * `key` - The key of the solved request.
+/// * `package_ids` - The package IDs of the cover found for it.
+///
+/// # Errors
+///
+/// This function returns an error if the connection to Redis fails, or if the solution cannot be
+/// serialized or stored.
+pub async fn record_warm_start(
+    redis_client: &redis::Client,
+    key: &CompositeKey,
+    package_ids: Vec<usize>,
+) -> anyhow::Result<()> {
+    let mut connection = redis_client.get_multiplexed_tokio_connection().await?;
+
+    let list_key = warm_start_key(key);
+    let warm_start = serde_json::to_string(&WarmStart {
+        game_ids: key.ids.clone(),
+        package_ids,
+    })
+    .context("Failed to serialize warm start")?;
+
+    let _: () = redis::pipe()
+        .atomic()
+        .lpush(&list_key, warm_start)
+        .ltrim(&list_key, 0, WARM_START_CAPACITY - 1)
+        .expire(&list_key, CACHE_TTL as i64)
+        .query_async(&mut connection)
+        .await?;
+    Ok(())
+}
+
+/// Looks up the recorded solution of the request closest to the one of `key`.
+///
+/// Only solutions of requests with the same fetch options are considered, as the options decide
+/// which packages are eligible and how they are priced.
+///
+/// # Arguments
+///
+/// * `redis_client` - A reference to the Redis client used to connect to the Redis server.
+/// * `key` - The key of the request to warm-start.
+/// * `max_distance` - The maximum [`WarmStart::distance`] between the games of both requests.
+///
+/// # Returns
+///
+/// - `Ok(Some(WarmStart))` with the closest solution within `max_distance`.
+/// - `Ok(None)` if no solution is close enough.
+///
+/// # Errors
+///
+/// This function returns an error if the connection to Redis fails, or if a recorded solution
+/// cannot be deserialized.
+pub async fn find_warm_start(
+    redis_client: &redis::Client,
+    key: &CompositeKey,
+    max_distance: f64,
+) -> anyhow::Result<Option<WarmStart>> {
+    let mut connection = redis_client.get_multiplexed_tokio_connection().await?;
+
+    let recorded: Vec<String> = connection.lrange(warm_start_key(key), 0, -1).await?;
+
+    let mut closest: Option<(f64, WarmStart)> = None;
+    for value in recorded {
+        let warm_start: WarmStart =
+            serde_json::from_str(&value).context("Failed to deserialize warm start")?;
+        let distance = warm_start.distance(&key.ids);
+
+        if distance <= max_distance && closest.as_ref().is_none_or(|(d, _)| distance < *d) {
+            closest = Some((distance, warm_start));
+        }
+    }
+
+    Ok(closest.map(|(_, warm_start)| warm_start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        let warm_start = WarmStart {
+            game_ids: vec![1, 2, 3, 4],
+            package_ids: vec![7],
+        };
+
+        assert_eq!(warm_start.distance(&[4, 3, 2, 1]), 0.0);
+        assert_eq!(warm_start.distance(&[1, 2, 3, 4, 5]), 0.2);
+        assert_eq!(warm_start.distance(&[1, 2, 3]), 0.25);
+        assert_eq!(warm_start.distance(&[5, 6]), 1.0);
+    }
+}
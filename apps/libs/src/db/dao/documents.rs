@@ -1,5 +1,7 @@
 use mongodb::bson::{doc, Bson::Null, Document};
 
+use crate::models::fetch_types::PackageFilter;
+
 pub fn filter_teams(teams: &[String]) -> Document {
     doc! {
         "$or": [
@@ -15,6 +17,12 @@ pub fn filter_tournaments(tournaments: &[String]) -> Document {
     }
 }
 
+pub fn filter_game_ids(game_ids: &[u32]) -> Document {
+    doc! {
+        "game_id": { "$in": game_ids }
+    }
+}
+
 pub fn aggregate_teams_pipeline() -> Vec<Document> {
     vec![
         doc! {
@@ -104,7 +112,14 @@ pub fn aggregate_game_ids_pipeline(input: &[String]) -> Vec<Document> {
     ]
 }
 
-pub fn preprocess_subsets_pipeline(game_ids: &[u32]) -> Vec<Document> {
+pub fn preprocess_subsets_pipeline(game_ids: &[u32], filter: &PackageFilter) -> Vec<Document> {
+    let mut match_stage = doc! {
+        "offers": doc! {
+            "$ne": []
+        }
+    };
+    match_stage.extend(filter.exclusion_conditions());
+
     vec![
         doc! {
             "$lookup": doc! {
@@ -152,11 +167,7 @@ pub fn preprocess_subsets_pipeline(game_ids: &[u32]) -> Vec<Document> {
             }
         },
         doc! {
-            "$match": doc! {
-                "offers": doc! {
-                    "$ne": []
-                }
-            }
+            "$match": match_stage
         },
         doc! {
             "$project": doc! {
@@ -88,6 +88,14 @@ impl GameDao {
         Ok(games)
     }
 
+    pub async fn find_games_by_ids(&self, game_ids: &[usize]) -> anyhow::Result<Vec<GameSchema>> {
+        let game_ids: Vec<u32> = game_ids.iter().map(|&x| x as u32).collect();
+        let filter = documents::filter_game_ids(&game_ids);
+        let cursor = self.collection.find(filter).await?;
+        let games = cursor.try_collect().await?;
+        Ok(games)
+    }
+
     pub async fn aggregate_game_ids(&self, input: Vec<String>) -> anyhow::Result<Vec<usize>> {
         let pipeline = documents::aggregate_game_ids_pipeline(&input);
         let mut cursor = self.collection.aggregate(pipeline).await?;
@@ -202,6 +210,18 @@ mod tests {
         assert!(games.len() == 51);
     }
 
+    #[tokio::test]
+    async fn test_find_games_by_ids() {
+        dotenv::dotenv().ok();
+        let uri = env::var("MONGODB_URI").expect("MONGODB_URI must be set in env");
+        let mongo_client = MongoClient::init(&uri, DATABASE_NAME).await;
+        let game_dao = GameDao::new(mongo_client.get_collection(GAME_COLLECTION_NAME));
+
+        let games = game_dao.find_games_by_ids(&[52, 69, 76]).await.unwrap();
+
+        assert_eq!(games.len(), 3);
+    }
+
     #[tokio::test]
     async fn test_aggregate_game_ids_without_input() {
         dotenv::dotenv().ok();
@@ -3,7 +3,9 @@ use futures::TryStreamExt;
 use mongodb::{bson, Collection};
 
 use super::documents;
-use crate::models::{dtos::BestCombinationSubsetDto, schemas::StreamingPackageSchema};
+use crate::models::{
+    dtos::BestCombinationSubsetDto, fetch_types::PackageFilter, schemas::StreamingPackageSchema,
+};
 
 pub struct StreamingPackageDao {
     collection: Collection<StreamingPackageSchema>,
@@ -24,12 +26,15 @@ impl StreamingPackageDao {
         Ok(packages)
     }
 
+    /// Aggregates the packages offering any of the given games into subsets, leaving out the
+    /// packages excluded by `filter`.
     pub async fn aggregate_subsets_by_game_ids(
         &self,
         game_ids: &[usize],
+        filter: &PackageFilter,
     ) -> anyhow::Result<Vec<BestCombinationSubsetDto>> {
         let game_ids: Vec<u32> = game_ids.iter().map(|&x| x as u32).collect();
-        let pipeline = documents::preprocess_subsets_pipeline(&game_ids);
+        let pipeline = documents::preprocess_subsets_pipeline(&game_ids, filter);
         let mut cursor = self
             .collection
             .aggregate(pipeline)
@@ -102,7 +107,7 @@ mod tests {
             8560, 8845,
         ];
         let subsets = package_dao
-            .aggregate_subsets_by_game_ids(&game_ids)
+            .aggregate_subsets_by_game_ids(&game_ids, &PackageFilter::default())
             .await
             .unwrap();
 
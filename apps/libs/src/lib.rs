@@ -5,4 +5,5 @@ pub mod logging;
 pub mod messaging;
 pub mod metrics;
 pub mod models;
+pub mod solver;
 pub mod testing;
@@ -1,3 +1,8 @@
 mod mq;
+mod retry;
 
 pub use mq::{create_consumer, enqueue_job, get_channel, init_mq, MqChannel};
+pub use retry::{
+    backoff_delay, dead_letter_exchange_name, dead_letter_queue_name, job_queue_name, retry_count,
+    retry_job, retry_queue_name,
+};
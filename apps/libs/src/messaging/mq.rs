@@ -1,11 +1,18 @@
 use anyhow::Context;
 use lapin::{
-    options::{BasicConsumeOptions, BasicPublishOptions, QueueDeclareOptions},
-    types::FieldTable,
-    BasicProperties, Channel, Connection, ConnectionProperties, Consumer,
+    options::{
+        BasicConsumeOptions, BasicPublishOptions, ExchangeDeclareOptions, QueueBindOptions,
+        QueueDeclareOptions,
+    },
+    types::{AMQPValue, FieldTable, ShortString},
+    BasicProperties, Channel, Connection, ConnectionProperties, Consumer, ExchangeKind,
 };
 use serde::Serialize;
 
+use super::retry::{
+    dead_letter_exchange_name, dead_letter_queue_name, job_queue_name, retry_queue_name,
+};
+
 /// A type alias for RabbitMQ's [`Channel`](https://docs.rs/lapin/latest/lapin/struct.Channel.html).
 ///
 /// `MqChannel` represents a dedicated channel for messaging operations within the application.
@@ -64,15 +71,23 @@ pub async fn get_channel(uri: &str) -> anyhow::Result<Channel> {
     Ok(channel)
 }
 
-/// Declares a queue in RabbitMQ using the provided channel.
+/// Declares the job queue of a queue name in RabbitMQ using the provided channel, along with its
+/// retry and dead-letter queues.
+///
+/// The jobs are published to and consumed from the [job queue](job_queue_name) of `queue_name`,
+/// not a queue named `queue_name` itself. Jobs which are rejected without being requeued are dead-lettered to the
+/// [dead-letter exchange](dead_letter_exchange_name) of the queue, which routes them to its
+/// [dead-letter queue](dead_letter_queue_name) for inspection. Jobs published to the
+/// [retry queue](retry_queue_name) with an expiration are dead-lettered back to the queue once it
+/// has passed, see [`retry_job`](super::retry_job).
 ///
 /// # Parameters
 /// - `channel`: The RabbitMQ `Channel` to declare the queue on.
-/// - `queue_name`: The name of the queue to declare.
+/// - `queue_name`: The name the job, retry and dead-letter queues are derived from.
 ///
 /// # Returns
-/// - `Ok(())`: Indicates the queue was successfully declared.
-/// - `Err(anyhow::Error)`: An error if the queue declaration fails.
+/// - `Ok(())`: Indicates the queues were successfully declared.
+/// - `Err(anyhow::Error)`: An error if a declaration fails.
 ///
 /// # Example
 /// ```no_run
@@ -90,15 +105,63 @@ pub async fn get_channel(uri: &str) -> anyhow::Result<Channel> {
 /// # Errors
 /// - This function will return an error if:
 ///   - The channel is invalid or closed.
-///   - The queue declaration fails due to permissions or configuration issues.
+///   - A declaration fails due to permissions or configuration issues, e.g. if the queue has been
+///     declared with different arguments before.
 pub async fn init_mq(channel: &Channel, queue_name: &str) -> anyhow::Result<()> {
+    let dead_letter_exchange = dead_letter_exchange_name(queue_name);
+    let dead_letter_queue = dead_letter_queue_name(queue_name);
+
+    channel
+        .exchange_declare(
+            &dead_letter_exchange,
+            ExchangeKind::Fanout,
+            ExchangeDeclareOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
     channel
         .queue_declare(
-            queue_name,
+            &dead_letter_queue,
             QueueDeclareOptions::default(),
             FieldTable::default(),
         )
         .await?;
+    channel
+        .queue_bind(
+            &dead_letter_queue,
+            &dead_letter_exchange,
+            "",
+            QueueBindOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
+
+    let job_queue = job_queue_name(queue_name);
+    let mut arguments = FieldTable::default();
+    arguments.insert(
+        ShortString::from("x-dead-letter-exchange"),
+        AMQPValue::LongString(dead_letter_exchange.into()),
+    );
+    channel
+        .queue_declare(&job_queue, QueueDeclareOptions::default(), arguments)
+        .await?;
+
+    let mut retry_arguments = FieldTable::default();
+    retry_arguments.insert(
+        ShortString::from("x-dead-letter-exchange"),
+        AMQPValue::LongString("".into()),
+    );
+    retry_arguments.insert(
+        ShortString::from("x-dead-letter-routing-key"),
+        AMQPValue::LongString(job_queue.into()),
+    );
+    channel
+        .queue_declare(
+            &retry_queue_name(queue_name),
+            QueueDeclareOptions::default(),
+            retry_arguments,
+        )
+        .await?;
 
     Ok(())
 }
@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use lapin::{
+    message::Delivery,
+    options::BasicPublishOptions,
+    types::{AMQPValue, FieldTable, ShortString},
+    BasicProperties, Channel,
+};
+
+/// Header counting how often a job has been retried.
+const RETRY_COUNT_HEADER: &str = "x-retry-count";
+
+/// Returns the name of the queue the jobs of `queue_name` are published to and consumed from.
+///
+/// The jobs used to be published to a queue named `queue_name` itself, which was declared without
+/// a dead-letter exchange. RabbitMQ refuses to redeclare an existing queue with different arguments
+/// (`PRECONDITION_FAILED`), so the jobs moved to a queue of their own. The former queue is left
+/// alone and can be deleted once it has been drained.
+pub fn job_queue_name(queue_name: &str) -> String {
+    format!("{}.jobs", queue_name)
+}
+
+/// Returns the name of the queue delayed retries of the jobs of `queue_name` wait in.
+///
+/// The queue has no consumer. A job published to it with an expiration is dead-lettered back to
+/// its [job queue](job_queue_name) once the expiration has passed.
+pub fn retry_queue_name(queue_name: &str) -> String {
+    format!("{}.retry", queue_name)
+}
+
+/// Returns the name of the dead-letter exchange of the jobs of `queue_name`.
+pub fn dead_letter_exchange_name(queue_name: &str) -> String {
+    format!("{}.dlx", queue_name)
+}
+
+/// Returns the name of the queue collecting the dead-lettered jobs of `queue_name`.
+pub fn dead_letter_queue_name(queue_name: &str) -> String {
+    format!("{}.dead", queue_name)
+}
+
+/// Returns how often the job of a delivery has been retried so far.
+pub fn retry_count(delivery: &Delivery) -> u32 {
+    delivery
+        .properties
+        .headers()
+        .as_ref()
+        .and_then(|headers| headers.inner().get(RETRY_COUNT_HEADER))
+        .and_then(|value| value.as_long_long_int())
+        .and_then(|retries| u32::try_from(retries).ok())
+        .unwrap_or(0)
+}
+
+/// Computes the delay before the next retry of a job with bounded exponential backoff.
+///
+/// # Parameters
+/// - `retries`: How often the job has been retried so far.
+/// - `base_delay`: The delay before the first retry, which doubles with every further retry.
+/// - `max_delay`: The upper bound of the delay.
+///
+/// # Returns
+/// The delay of the next retry, `base_delay * 2^retries` capped at `max_delay`.
+pub fn backoff_delay(retries: u32, base_delay: Duration, max_delay: Duration) -> Duration {
+    2u32.checked_pow(retries)
+        .and_then(|factor| base_delay.checked_mul(factor))
+        .map_or(max_delay, |delay| delay.min(max_delay))
+}
+
+/// Publishes a failed job again, to be redelivered to its queue after a delay.
+///
+/// The job waits in the [retry queue](retry_queue_name) of `queue_name`, which has to be declared
+/// with [`init_mq`](super::init_mq). The retry count is carried in the headers of the message, see
+/// [`retry_count`].
+///
+/// # Parameters
+/// - `channel`: The RabbitMQ `Channel` to publish the job on.
+/// - `queue_name`: The name of the queue the job is redelivered to, see [`job_queue_name`].
+/// - `payload`: The unchanged payload of the job.
+/// - `retries`: How often the job has been retried, including this retry.
+/// - `delay`: The delay before the job is redelivered.
+///
+/// # Returns
+/// - `Ok(())`: Indicates the job was successfully published to the retry queue.
+/// - `Err(anyhow::Error)`: An error if the message publication fails.
+///
+/// # Errors
+/// - This function will return an error if:
+///   - The channel is invalid or closed.
+///   - Publishing the message fails due to configuration or permissions issues.
+pub async fn retry_job(
+    channel: &Channel,
+    queue_name: &str,
+    payload: &[u8],
+    retries: u32,
+    delay: Duration,
+) -> anyhow::Result<()> {
+    let mut headers = FieldTable::default();
+    headers.insert(
+        ShortString::from(RETRY_COUNT_HEADER),
+        AMQPValue::LongLongInt(retries.into()),
+    );
+
+    channel
+        .basic_publish(
+            "",
+            &retry_queue_name(queue_name),
+            BasicPublishOptions::default(),
+            payload,
+            BasicProperties::default()
+                .with_headers(headers)
+                .with_expiration(ShortString::from(delay.as_millis().to_string())),
+        )
+        .await?
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_names() {
+        assert_eq!(job_queue_name("queue_task"), "queue_task.jobs");
+        assert_eq!(retry_queue_name("queue_task"), "queue_task.retry");
+        assert_eq!(dead_letter_exchange_name("queue_task"), "queue_task.dlx");
+        assert_eq!(dead_letter_queue_name("queue_task"), "queue_task.dead");
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let base_delay = Duration::from_secs(1);
+        let max_delay = Duration::from_secs(30);
+
+        assert_eq!(
+            backoff_delay(0, base_delay, max_delay),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            backoff_delay(3, base_delay, max_delay),
+            Duration::from_secs(8)
+        );
+        assert_eq!(backoff_delay(5, base_delay, max_delay), max_delay);
+        assert_eq!(backoff_delay(64, base_delay, max_delay), max_delay);
+    }
+}
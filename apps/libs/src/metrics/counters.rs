@@ -9,6 +9,7 @@ enum MetricLabelName {
     Method,
     Endpoint,
     Status,
+    Start,
 }
 
 impl MetricLabelName {
@@ -18,6 +19,7 @@ impl MetricLabelName {
             MetricLabelName::Method => "method",
             MetricLabelName::Endpoint => "endpoint",
             MetricLabelName::Status => "status",
+            MetricLabelName::Start => "start",
         }
     }
 }
@@ -61,6 +63,18 @@ pub static ERROR_COUNT: Lazy<IntCounter> = Lazy::new(|| {
         .expect("Failed to create ERROR_COUNT")
 });
 
+/// A counter to track the runs of the worker's set cover solvers.
+///
+/// Labels metrics with whether the search has been warm-started from the cover of a similar
+/// request (`warm`) or not (`cold`).
+pub static SOLVER_RUN_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("worker_solver_runs_total", "Total number of solver runs"),
+        &[MetricLabelName::Start.as_str()],
+    )
+    .expect("Failed to create SOLVER_RUN_COUNT")
+});
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,6 +151,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_solver_run_count_increment() {
+        let cold_runs = SOLVER_RUN_COUNT.with_label_values(&["cold"]).get();
+
+        SOLVER_RUN_COUNT.with_label_values(&["cold"]).inc_by(2);
+
+        assert_eq!(
+            SOLVER_RUN_COUNT.with_label_values(&["cold"]).get(),
+            cold_runs + 2
+        );
+    }
+
     #[test]
     fn test_request_duration_observe() {
         reset_metrics();
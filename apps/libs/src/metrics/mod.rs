@@ -3,6 +3,7 @@ mod handler;
 mod middleware;
 mod registry;
 
+pub use counters::SOLVER_RUN_COUNT;
 pub use handler::metrics_handler;
 pub use middleware::MetricsMiddleware;
 pub use registry::init_metrics;
@@ -23,6 +23,10 @@ fn register_metrics(registry: &Registry) {
     registry
         .register(Box::new(counters::ERROR_COUNT.clone()))
         .expect("Failed to register ERROR_COUNT");
+
+    registry
+        .register(Box::new(counters::SOLVER_RUN_COUNT.clone()))
+        .expect("Failed to register SOLVER_RUN_COUNT");
 }
 
 /// Initializes the Prometheus metrics system.
@@ -101,12 +105,16 @@ mod tests {
             .with_label_values(&["GET", "/test", "200"])
             .observe(0.123);
         counters::ERROR_COUNT.inc();
+        counters::SOLVER_RUN_COUNT
+            .with_label_values(&["warm"])
+            .inc();
 
         let encoded_metrics = gather_metrics(&registry);
 
         assert!(encoded_metrics.contains("api_request_duration_seconds"));
         assert!(encoded_metrics.contains("api_requests_total"));
         assert!(encoded_metrics.contains("api_errors_total"));
+        assert!(encoded_metrics.contains("worker_solver_runs_total"));
         assert!(encoded_metrics.contains("0.123"));
         assert!(encoded_metrics.contains("1"));
     }
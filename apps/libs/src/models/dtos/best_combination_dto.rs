@@ -1,15 +1,48 @@
-use async_graphql::SimpleObject;
+use async_graphql::{ComplexObject, SimpleObject};
 use serde::{Deserialize, Serialize};
 
-use super::BestCombinationPackageDto;
+use super::{
+    BestCombinationPackageDto, GameCoverageDto, GameCoveragePageDto, SeasonScheduleDto,
+    UncoveredGameDto,
+};
 
 #[derive(SimpleObject, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[graphql(complex)]
 pub struct BestCombinationDto {
     pub packages: Vec<BestCombinationPackageDto>,
     pub combined_monthly_price_cents: usize,
     pub combined_monthly_price_yearly_subscription_in_cents: usize,
     pub combined_coverage: u8,
     pub index: usize,
+    /// The combined monthly price in cents of the packages, each priced by the subscription it is
+    /// billed with. It is `None` if a package can't be booked with its billing option.
+    #[serde(default)]
+    pub combined_billed_price_cents: Option<usize>,
+    /// Whether the solver proved that no better combination exists for the request.
+    #[serde(default)]
+    pub proven_optimal: bool,
+    /// Whether the search ran out of its time or node budget, so the combination is only the best
+    /// one found so far.
+    #[serde(default)]
+    pub timed_out: bool,
+    /// The month-by-month subscription plan, if the combination has been computed for a schedule.
+    #[serde(default)]
+    pub schedule: Option<SeasonScheduleDto>,
+    /// The requested games the combination doesn't cover, ordered by their game ID.
+    #[serde(default)]
+    pub uncovered_games: Vec<UncoveredGameDto>,
+    /// The IDs of the redundant packages which have been removed from the combination the solver
+    /// returned, as their games are covered by the other packages anyway.
+    #[serde(default)]
+    pub removed_package_ids: Vec<usize>,
+    /// The billed monthly price in cents saved by removing the redundant packages.
+    #[serde(default)]
+    pub saved_monthly_price_cents: usize,
+    /// The coverage of every requested game, ordered by kickoff. It is exposed page by page via
+    /// [`BestCombinationDto::game_coverage`], and only built if the request asked for it.
+    #[graphql(skip)]
+    #[serde(default)]
+    pub games: Vec<GameCoverageDto>,
 }
 
 impl BestCombinationDto {
@@ -21,11 +54,19 @@ impl BestCombinationDto {
         index: usize,
     ) -> Self {
         BestCombinationDto {
+            combined_billed_price_cents: combined_billed_price_cents(&packages),
             packages,
             combined_monthly_price_cents,
             combined_monthly_price_yearly_subscription_in_cents,
             combined_coverage,
             index,
+            proven_optimal: false,
+            timed_out: false,
+            schedule: None,
+            uncovered_games: Vec::new(),
+            removed_package_ids: Vec::new(),
+            saved_monthly_price_cents: 0,
+            games: Vec::new(),
         }
     }
 
@@ -38,3 +79,27 @@ impl BestCombinationDto {
             && self.combined_coverage == other.combined_coverage
     }
 }
+
+/// Sums the billed prices of `packages`, or returns `None` if one of them can't be booked with its
+/// billing option.
+fn combined_billed_price_cents(packages: &[BestCombinationPackageDto]) -> Option<usize> {
+    packages
+        .iter()
+        .map(BestCombinationPackageDto::billed_price_cents)
+        .sum()
+}
+
+#[ComplexObject]
+impl BestCombinationDto {
+    /// The coverage of the requested games by the packages of the combination, ordered by kickoff.
+    /// Unlike the per-tournament `coverage` of the packages, it tells for every single game which
+    /// packages cover it and whether live or only as highlights. It is empty unless the combination
+    /// has been requested with `includeGameCoverage`.
+    async fn game_coverage(
+        &self,
+        #[graphql(default = 0)] offset: usize,
+        #[graphql(default = 20, validator(minimum = 1, maximum = 100))] limit: usize,
+    ) -> GameCoveragePageDto {
+        GameCoveragePageDto::new(&self.games, offset, limit)
+    }
+}
@@ -3,6 +3,9 @@ use std::collections::HashMap;
 use async_graphql::SimpleObject;
 use serde::{Deserialize, Serialize};
 
+use super::PackageExplanationDto;
+use crate::models::fetch_types::BillingOption;
+
 #[derive(SimpleObject, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 pub struct BestCombinationPackageDto {
     pub id: usize,
@@ -10,6 +13,15 @@ pub struct BestCombinationPackageDto {
     pub coverage: HashMap<String, (u8, u8)>,
     pub monthly_price_cents: Option<usize>,
     pub monthly_price_yearly_subscription_in_cents: usize,
+    /// The subscription the package is billed with.
+    #[serde(default)]
+    pub billing: BillingOption,
+    /// Why the package is part of the combination.
+    #[serde(default)]
+    pub explanation: Option<PackageExplanationDto>,
+    /// Whether the user already owns the package, so it doesn't add to the prices.
+    #[serde(default)]
+    pub owned: bool,
 }
 
 impl BestCombinationPackageDto {
@@ -29,6 +41,18 @@ impl BestCombinationPackageDto {
                 .collect(),
             monthly_price_cents,
             monthly_price_yearly_subscription_in_cents,
+            billing: BillingOption::default(),
+            explanation: None,
+            owned: false,
+        }
+    }
+
+    /// Returns the monthly price of the package under its billing option, or `None` if it can't
+    /// be booked that way.
+    pub fn billed_price_cents(&self) -> Option<usize> {
+        match self.billing {
+            BillingOption::Monthly => self.monthly_price_cents,
+            BillingOption::Yearly => Some(self.monthly_price_yearly_subscription_in_cents),
         }
     }
 }
@@ -3,7 +3,7 @@ use std::collections::BTreeSet;
 use serde::{Deserialize, Serialize};
 
 use super::BestCombinationElementDto;
-use crate::models::util::deserialize_optional_numeric_from_string;
+use crate::models::{fetch_types::BillingOption, util::deserialize_optional_numeric_from_string};
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct BestCombinationSubsetDto {
@@ -13,6 +13,9 @@ pub struct BestCombinationSubsetDto {
     #[serde(deserialize_with = "deserialize_optional_numeric_from_string", default)]
     pub monthly_price_cents: Option<usize>,
     pub monthly_price_yearly_subscription_in_cents: usize,
+    /// The subscription the package is billed with, as chosen by the request's pricing model.
+    #[serde(default)]
+    pub billing: BillingOption,
 }
 
 impl BestCombinationSubsetDto {
@@ -29,12 +32,22 @@ impl BestCombinationSubsetDto {
             elements,
             monthly_price_cents,
             monthly_price_yearly_subscription_in_cents,
+            billing: BillingOption::default(),
         }
     }
 
     pub fn element_ids(&self) -> BTreeSet<usize> {
         self.elements.iter().map(|elem| elem.game_id).collect()
     }
+
+    /// Returns the monthly price of the package under its billing option, or `None` if it can't
+    /// be booked that way (e.g. a package without a monthly-cancellable subscription).
+    pub fn billed_price_cents(&self) -> Option<usize> {
+        match self.billing {
+            BillingOption::Monthly => self.monthly_price_cents,
+            BillingOption::Yearly => Some(self.monthly_price_yearly_subscription_in_cents),
+        }
+    }
 }
 
 #[cfg(test)]
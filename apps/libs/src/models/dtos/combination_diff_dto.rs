@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+
+use super::{BestCombinationDto, BestCombinationPackageDto, GameCoverageDto};
+
+/// How the live and highlights coverage of a single game changes between two combinations.
+#[derive(SimpleObject, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct GameCoverageChangeDto {
+    pub game_id: usize,
+    pub live_before: bool,
+    pub live_after: bool,
+    pub highlights_before: bool,
+    pub highlights_after: bool,
+}
+
+/// The differences between two combinations of the same result, from the combination at
+/// `from_index` to the one at `to_index`.
+#[derive(SimpleObject, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct CombinationDiffDto {
+    pub from_index: usize,
+    pub to_index: usize,
+    /// The packages only part of the `to_index` combination.
+    pub added_packages: Vec<BestCombinationPackageDto>,
+    /// The packages only part of the `from_index` combination.
+    pub removed_packages: Vec<BestCombinationPackageDto>,
+    pub monthly_price_delta_cents: i64,
+    pub monthly_price_yearly_subscription_delta_cents: i64,
+    /// The games only covered by the `to_index` combination.
+    pub gained_game_ids: Vec<usize>,
+    /// The games only covered by the `from_index` combination.
+    pub lost_game_ids: Vec<usize>,
+    /// The games whose live or highlights coverage differs, including the gained and lost ones.
+    pub coverage_changes: Vec<GameCoverageChangeDto>,
+}
+
+impl CombinationDiffDto {
+    pub fn new(from: &BestCombinationDto, to: &BestCombinationDto) -> Self {
+        let packages_only_in = |a: &BestCombinationDto, b: &BestCombinationDto| {
+            a.packages
+                .iter()
+                .filter(|p| !b.packages.iter().any(|other| other.id == p.id))
+                .cloned()
+                .collect()
+        };
+
+        let games_by_id = |c: &BestCombinationDto| -> BTreeMap<usize, GameCoverageDto> {
+            c.games.iter().map(|g| (g.game_id, g.clone())).collect()
+        };
+        let from_games = games_by_id(from);
+        let to_games = games_by_id(to);
+
+        // Covered, live and highlights status of a game, which is uncovered if it's unknown
+        let status = |games: &BTreeMap<usize, GameCoverageDto>, game_id: &usize| {
+            games.get(game_id).map_or((false, false, false), |g| {
+                (!g.package_ids.is_empty(), g.live, g.highlights)
+            })
+        };
+
+        let mut game_ids: Vec<usize> = from_games.keys().chain(to_games.keys()).copied().collect();
+        game_ids.sort();
+        game_ids.dedup();
+
+        let mut gained_game_ids = Vec::new();
+        let mut lost_game_ids = Vec::new();
+        let mut coverage_changes = Vec::new();
+
+        for game_id in game_ids {
+            let (covered_before, live_before, highlights_before) = status(&from_games, &game_id);
+            let (covered_after, live_after, highlights_after) = status(&to_games, &game_id);
+
+            match (covered_before, covered_after) {
+                (false, true) => gained_game_ids.push(game_id),
+                (true, false) => lost_game_ids.push(game_id),
+                _ => {}
+            }
+
+            if (live_before, highlights_before) != (live_after, highlights_after) {
+                coverage_changes.push(GameCoverageChangeDto {
+                    game_id,
+                    live_before,
+                    live_after,
+                    highlights_before,
+                    highlights_after,
+                });
+            }
+        }
+
+        CombinationDiffDto {
+            from_index: from.index,
+            to_index: to.index,
+            added_packages: packages_only_in(to, from),
+            removed_packages: packages_only_in(from, to),
+            monthly_price_delta_cents: to.combined_monthly_price_cents as i64
+                - from.combined_monthly_price_cents as i64,
+            monthly_price_yearly_subscription_delta_cents: to
+                .combined_monthly_price_yearly_subscription_in_cents
+                as i64
+                - from.combined_monthly_price_yearly_subscription_in_cents as i64,
+            gained_game_ids,
+            lost_game_ids,
+            coverage_changes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(game_id: usize, live: bool, highlights: bool) -> GameCoverageDto {
+        GameCoverageDto {
+            game_id,
+            team_home: String::new(),
+            team_away: String::new(),
+            tournament_name: String::new(),
+            starts_at: String::new(),
+            package_ids: if live || highlights { vec![1] } else { vec![] },
+            live,
+            highlights,
+        }
+    }
+
+    fn combination(
+        index: usize,
+        package_ids: &[usize],
+        price: usize,
+        games: Vec<GameCoverageDto>,
+    ) -> BestCombinationDto {
+        let packages = package_ids
+            .iter()
+            .map(|&id| BestCombinationPackageDto::new(id, "", vec![], Some(price), price))
+            .collect();
+        let mut combination = BestCombinationDto::new(packages, price, price * 2, 100, index);
+        combination.games = games;
+        combination
+    }
+
+    #[test]
+    fn test_combination_diff() {
+        let from = combination(
+            0,
+            &[1, 2],
+            10,
+            vec![
+                game(1, true, true),
+                game(2, true, false),
+                game(3, false, false),
+            ],
+        );
+        let to = combination(
+            1,
+            &[2, 3],
+            15,
+            vec![
+                game(1, false, true),
+                game(2, false, false),
+                game(3, true, true),
+            ],
+        );
+
+        let diff = CombinationDiffDto::new(&from, &to);
+
+        assert_eq!(diff.from_index, 0);
+        assert_eq!(diff.to_index, 1);
+        assert_eq!(
+            diff.added_packages.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![3]
+        );
+        assert_eq!(
+            diff.removed_packages
+                .iter()
+                .map(|p| p.id)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert_eq!(diff.monthly_price_delta_cents, 5);
+        assert_eq!(diff.monthly_price_yearly_subscription_delta_cents, 10);
+        assert_eq!(diff.gained_game_ids, vec![3]);
+        assert_eq!(diff.lost_game_ids, vec![2]);
+        assert_eq!(
+            diff.coverage_changes
+                .iter()
+                .map(|c| c.game_id)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert!(diff.coverage_changes[0].live_before && !diff.coverage_changes[0].live_after);
+    }
+}
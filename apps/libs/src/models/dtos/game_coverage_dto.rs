@@ -0,0 +1,78 @@
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+
+/// How a single requested game is covered by the packages of a combination.
+#[derive(SimpleObject, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct GameCoverageDto {
+    pub game_id: usize,
+    pub team_home: String,
+    pub team_away: String,
+    pub tournament_name: String,
+    pub starts_at: String,
+    /// The packages of the combination covering the game, empty if it isn't covered.
+    pub package_ids: Vec<usize>,
+    /// Whether one of the covering packages streams the game live.
+    pub live: bool,
+    /// Whether one of the covering packages offers the highlights of the game.
+    pub highlights: bool,
+}
+
+/// A page of the per-game coverage of a combination, ordered by kickoff.
+#[derive(SimpleObject, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct GameCoveragePageDto {
+    pub games: Vec<GameCoverageDto>,
+    pub offset: usize,
+    /// The number of games across all pages.
+    pub total: usize,
+    pub has_more: bool,
+}
+
+impl GameCoveragePageDto {
+    /// Cuts the page of at most `limit` games starting at `offset` out of all games.
+    pub fn new(games: &[GameCoverageDto], offset: usize, limit: usize) -> Self {
+        let page: Vec<GameCoverageDto> = games.iter().skip(offset).take(limit).cloned().collect();
+
+        GameCoveragePageDto {
+            has_more: offset + page.len() < games.len(),
+            games: page,
+            offset,
+            total: games.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(game_id: usize) -> GameCoverageDto {
+        GameCoverageDto {
+            game_id,
+            team_home: String::new(),
+            team_away: String::new(),
+            tournament_name: String::new(),
+            starts_at: String::new(),
+            package_ids: vec![],
+            live: false,
+            highlights: false,
+        }
+    }
+
+    #[test]
+    fn test_game_coverage_page() {
+        let games: Vec<GameCoverageDto> = (1..=5).map(game).collect();
+
+        let page = GameCoveragePageDto::new(&games, 0, 2);
+        assert_eq!(page.games, vec![game(1), game(2)]);
+        assert_eq!(page.total, 5);
+        assert!(page.has_more);
+
+        let page = GameCoveragePageDto::new(&games, 4, 2);
+        assert_eq!(page.games, vec![game(5)]);
+        assert!(!page.has_more);
+
+        let page = GameCoveragePageDto::new(&games, 10, 2);
+        assert!(page.games.is_empty());
+        assert!(!page.has_more);
+    }
+}
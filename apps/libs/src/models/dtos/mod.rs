@@ -2,14 +2,24 @@ mod best_combination_dto;
 mod best_combination_element_dto;
 mod best_combination_package_dto;
 mod best_combination_subset_dto;
+mod combination_diff_dto;
+mod game_coverage_dto;
 mod game_dto;
+mod package_explanation_dto;
+mod season_schedule_dto;
 mod streaming_offer_dto;
 mod streaming_package_dto;
+mod uncovered_game_dto;
 
 pub use best_combination_dto::BestCombinationDto;
 pub use best_combination_element_dto::BestCombinationElementDto;
 pub use best_combination_package_dto::BestCombinationPackageDto;
 pub use best_combination_subset_dto::BestCombinationSubsetDto;
+pub use combination_diff_dto::{CombinationDiffDto, GameCoverageChangeDto};
+pub use game_coverage_dto::{GameCoverageDto, GameCoveragePageDto};
 pub use game_dto::GameDto;
+pub use package_explanation_dto::{PackageExplanationDto, PackageReplacementDto};
+pub use season_schedule_dto::{MonthlyPlanDto, SeasonScheduleDto};
 pub use streaming_offer_dto::StreamingOfferDto;
 pub use streaming_package_dto::StreamingPackageDto;
+pub use uncovered_game_dto::UncoveredGameDto;
@@ -0,0 +1,23 @@
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+
+/// A package outside of a combination which could take the place of one of its packages.
+#[derive(SimpleObject, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct PackageReplacementDto {
+    pub id: usize,
+    pub name: String,
+    /// The monthly price of the package under its billing option.
+    pub billed_price_cents: usize,
+}
+
+/// Explains why a package is part of a combination.
+#[derive(SimpleObject, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct PackageExplanationDto {
+    /// The requested games which no other package of the combination covers.
+    pub unique_game_ids: Vec<usize>,
+    /// The percentage points the combined coverage would drop by without the package.
+    pub coverage_loss: u8,
+    /// The cheapest bookable package outside of the combination which covers all of the unique
+    /// games, if there is one. Packages without unique games need no replacement.
+    pub cheapest_replacement: Option<PackageReplacementDto>,
+}
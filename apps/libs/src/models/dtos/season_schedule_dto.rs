@@ -0,0 +1,38 @@
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+
+/// The packages to hold during a single calendar month of a season.
+#[derive(SimpleObject, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct MonthlyPlanDto {
+    /// The calendar month in the format `YYYY-MM`.
+    pub month: String,
+    pub package_ids: Vec<usize>,
+    /// The number of requested games taking place in this month.
+    pub game_count: usize,
+    /// The number of those games covered by the packages held in this month.
+    pub covered_game_count: usize,
+    pub monthly_price_cents: usize,
+}
+
+/// A month-by-month subscription plan over the calendar of the requested games.
+///
+/// The plan only holds monthly-cancellable subscriptions during the months games take place in.
+/// For comparison, it also contains the cheapest cover of the whole season with yearly
+/// subscriptions, which have to be paid for twelve months per started year of the season.
+#[derive(SimpleObject, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct SeasonScheduleDto {
+    pub months: Vec<MonthlyPlanDto>,
+    /// The total cost of the month-by-month plan over the season.
+    pub total_price_cents: usize,
+    pub yearly_package_ids: Vec<usize>,
+    /// The total cost of the cheapest yearly subscriptions covering the season.
+    pub yearly_total_price_cents: Option<usize>,
+}
+
+impl SeasonScheduleDto {
+    /// Returns whether the month-by-month plan is cheaper than subscribing yearly.
+    pub fn is_cheaper_than_yearly(&self) -> bool {
+        self.yearly_total_price_cents
+            .is_none_or(|yearly| self.total_price_cents < yearly)
+    }
+}
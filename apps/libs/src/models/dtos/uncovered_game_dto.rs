@@ -0,0 +1,28 @@
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{fetch_types::UncoveredReason, schemas::GameSchema};
+
+/// A requested game which a combination doesn't cover.
+#[derive(SimpleObject, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct UncoveredGameDto {
+    pub game_id: usize,
+    pub team_home: String,
+    pub team_away: String,
+    pub tournament_name: String,
+    pub starts_at: String,
+    pub reason: UncoveredReason,
+}
+
+impl UncoveredGameDto {
+    pub fn new(game: &GameSchema, reason: UncoveredReason) -> Self {
+        UncoveredGameDto {
+            game_id: game.game_id as usize,
+            team_home: game.team_home.clone(),
+            team_away: game.team_away.clone(),
+            tournament_name: game.tournament_name.clone(),
+            starts_at: game.starts_at.clone(),
+            reason,
+        }
+    }
+}
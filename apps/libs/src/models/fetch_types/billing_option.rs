@@ -0,0 +1,14 @@
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+
+/// The subscription a streaming package is billed with.
+///
+/// * `Monthly` - The monthly-cancellable subscription (`monthly_price_cents`).
+/// * `Yearly` - The yearly subscription, paid monthly
+///   (`monthly_price_yearly_subscription_in_cents`).
+#[derive(Enum, Copy, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize, Debug)]
+pub enum BillingOption {
+    #[default]
+    Monthly,
+    Yearly,
+}
@@ -0,0 +1,54 @@
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+
+use crate::models::dtos::BestCombinationElementDto;
+
+/// Selects which kind of offer counts as covering a game.
+///
+/// * `Any` - A game is covered by every package that has an offer for it.
+/// * `Live` - A game is only covered if a package streams it live.
+/// * `Highlights` - A game is only covered if a package offers its highlights.
+/// * `LiveAndHighlights` - A game is only covered if a single package offers it live and as
+///   highlights.
+#[derive(Enum, Copy, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize, Debug)]
+pub enum CoverageRequirement {
+    #[default]
+    Any,
+    Live,
+    Highlights,
+    LiveAndHighlights,
+}
+
+impl CoverageRequirement {
+    /// Returns whether the offer described by `element` satisfies the requirement.
+    pub fn is_satisfied_by(&self, element: &BestCombinationElementDto) -> bool {
+        match self {
+            CoverageRequirement::Any => true,
+            CoverageRequirement::Live => element.live == 1,
+            CoverageRequirement::Highlights => element.highlights == 1,
+            CoverageRequirement::LiveAndHighlights => element.live == 1 && element.highlights == 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_satisfied_by() {
+        let live = BestCombinationElementDto::new(1, "A", 1, 0);
+        let highlights = BestCombinationElementDto::new(2, "A", 0, 1);
+        let both = BestCombinationElementDto::new(3, "A", 1, 1);
+
+        assert!(CoverageRequirement::Any.is_satisfied_by(&live));
+        assert!(CoverageRequirement::Any.is_satisfied_by(&highlights));
+        assert!(CoverageRequirement::Live.is_satisfied_by(&live));
+        assert!(!CoverageRequirement::Live.is_satisfied_by(&highlights));
+        assert!(!CoverageRequirement::Highlights.is_satisfied_by(&live));
+        assert!(CoverageRequirement::Highlights.is_satisfied_by(&highlights));
+        assert!(!CoverageRequirement::LiveAndHighlights.is_satisfied_by(&live));
+        assert!(!CoverageRequirement::LiveAndHighlights.is_satisfied_by(&highlights));
+        assert!(CoverageRequirement::LiveAndHighlights.is_satisfied_by(&both));
+    }
+}
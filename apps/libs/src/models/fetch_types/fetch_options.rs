@@ -6,27 +6,182 @@ use std::{
 use async_graphql::InputObject;
 use serde::{Deserialize, Serialize};
 
+use super::{CoverageRequirement, GamePriority, PackageFilter, PricingModel, SolverMode};
+
 #[derive(InputObject, Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct FetchOptions {
     #[graphql(default = 1)]
     #[graphql(validator(minimum = 1, maximum = 5))]
     pub limit: usize,
+    #[graphql(default)]
+    #[serde(default)]
+    pub mode: SolverMode,
+    #[graphql(default)]
+    #[serde(default)]
+    pub coverage: CoverageRequirement,
+    #[serde(default)]
+    pub max_monthly_price_cents: Option<usize>,
+    /// The maximum number of packages per combination. If the games can't be covered with that
+    /// many packages, the combinations covering the most games are returned instead.
+    #[graphql(validator(minimum = 1))]
+    #[serde(default)]
+    pub max_packages: Option<usize>,
+    #[graphql(default)]
+    #[serde(default)]
+    pub priorities: Vec<GamePriority>,
+    #[graphql(default)]
+    #[serde(default)]
+    pub pricing: PricingModel,
+    /// Name of the set cover solver to run, overriding the one selected by `mode`.
+    #[serde(default)]
+    pub solver: Option<String>,
+    /// Packages the user already subscribes to. They are part of every combination at no cost, so
+    /// the prices only reflect the additional spending.
+    #[graphql(default)]
+    #[serde(default)]
+    pub owned_package_ids: Vec<usize>,
+    /// The packages to exclude from or require in every combination.
+    #[graphql(default)]
+    #[serde(default)]
+    pub package_filter: PackageFilter,
+    /// Whether to build the coverage of every single game for the combinations, as paged through by
+    /// their `gameCoverage`. It lists all requested games, so it is only built when asked for.
+    #[graphql(default)]
+    #[serde(default)]
+    pub include_game_coverage: bool,
 }
 
 impl FetchOptions {
     pub fn new(limit: usize) -> FetchOptions {
-        FetchOptions { limit }
+        FetchOptions {
+            limit,
+            mode: SolverMode::default(),
+            coverage: CoverageRequirement::default(),
+            max_monthly_price_cents: None,
+            max_packages: None,
+            priorities: Vec::new(),
+            pricing: PricingModel::default(),
+            solver: None,
+            owned_package_ids: Vec::new(),
+            package_filter: PackageFilter::default(),
+            include_game_coverage: false,
+        }
+    }
+
+    /// Rejects options which contradict each other, instead of silently ignoring one of them.
+    ///
+    /// * A `Scheduled` plan covers every month with its cheapest packages, so it can't be capped
+    ///   by a monthly price or a number of packages.
+    /// * The registered solvers only search for covers, so a `solver` can't be combined with a
+    ///   price or package cap, which are searched with the budgeted solver.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first contradiction found.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let capped = self.max_monthly_price_cents.is_some() || self.max_packages.is_some();
+
+        if self.mode == SolverMode::Scheduled && capped {
+            anyhow::bail!(
+                "A maximum monthly price or number of packages can't be combined with the SCHEDULED mode"
+            );
+        }
+        if let (Some(solver), true) = (&self.solver, capped) {
+            anyhow::bail!(
+                "The solver {:?} can't be combined with a maximum monthly price or number of packages",
+                solver
+            );
+        }
+        Ok(())
     }
 }
 
 impl Display for FetchOptions {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        write!(f, "{}", self.limit)
+        write!(
+            f,
+            "{}:{:?}:{:?}:{:?}:{:?}:{:?}:{:?}:{:?}:{:?}:{:?}:{}",
+            self.limit,
+            self.mode,
+            self.coverage,
+            self.max_monthly_price_cents,
+            self.max_packages,
+            self.pricing,
+            self.solver,
+            self.priorities,
+            self.owned_package_ids,
+            self.package_filter,
+            self.include_game_coverage
+        )
     }
 }
 
 impl Hash for FetchOptions {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.limit.hash(state);
+        self.mode.hash(state);
+        self.coverage.hash(state);
+        self.max_monthly_price_cents.hash(state);
+        self.max_packages.hash(state);
+        self.pricing.hash(state);
+        self.solver.hash(state);
+
+        let mut sorted_priorities = self.priorities.clone();
+        sorted_priorities.sort();
+        sorted_priorities.hash(state);
+
+        let mut sorted_owned_package_ids = self.owned_package_ids.clone();
+        sorted_owned_package_ids.sort();
+        sorted_owned_package_ids.dedup();
+        sorted_owned_package_ids.hash(state);
+
+        self.package_filter.hash(state);
+        self.include_game_coverage.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_lists_every_option() {
+        let mut opts = FetchOptions::new(2);
+        opts.max_packages = Some(3);
+        opts.solver = Some("exact".to_string());
+        opts.owned_package_ids = vec![4, 13];
+        opts.include_game_coverage = true;
+
+        assert_eq!(
+            opts.to_string(),
+            format!(
+                "2:{:?}:{:?}:None:Some(3):{:?}:Some(\"exact\"):[]:[4, 13]:{:?}:true",
+                opts.mode, opts.coverage, opts.pricing, opts.package_filter
+            )
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_contradicting_options() {
+        let mut opts = FetchOptions::new(1);
+        opts.max_monthly_price_cents = Some(5000);
+        assert!(opts.validate().is_ok());
+
+        opts.mode = SolverMode::Exact;
+        assert!(opts.validate().is_ok());
+
+        opts.mode = SolverMode::Scheduled;
+        assert!(opts.validate().is_err());
+
+        opts.max_monthly_price_cents = None;
+        opts.max_packages = Some(2);
+        assert!(opts.validate().is_err());
+
+        opts.mode = SolverMode::Greedy;
+        opts.solver = Some("exact".to_string());
+        assert!(opts.validate().is_err());
+
+        opts.max_packages = None;
+        assert!(opts.validate().is_ok());
     }
 }
@@ -1,12 +1,22 @@
 use async_graphql::SimpleObject;
 use serde::Serialize;
 
-use super::FetchStatus;
-use crate::models::dtos::BestCombinationDto;
+use super::{FetchStatus, JobProgress};
+use crate::models::dtos::{BestCombinationDto, UncoveredGameDto};
 
 #[derive(SimpleObject, Serialize)]
 pub struct FetchResult {
     pub status: FetchStatus,
     pub ids: Vec<usize>,
     pub data: Option<Vec<BestCombinationDto>>,
+    /// Whether the data is only the best-so-far answer of a search that ran out of its time or
+    /// node budget.
+    pub partial: bool,
+    /// The requested games which no package offers or the options of the request rule out, so no
+    /// combination can cover them.
+    pub unavailable_games: Vec<UncoveredGameDto>,
+    /// The progress of the job while it is running.
+    pub progress: Option<JobProgress>,
+    /// Why the job failed, if it did.
+    pub failure_reason: Option<String>,
 }
@@ -1,9 +1,16 @@
 use async_graphql::Enum;
 use serde::{Deserialize, Serialize};
 
+/// The status of a requested job.
+///
+/// * `Ready` - The job has completed and its data is available.
+/// * `Queued` - The job waits for a worker to pick it up.
+/// * `Running` - A worker is computing the job, see the progress of the result.
+/// * `Error` - The job failed or couldn't be enqueued, see the failure reason of the result.
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub enum FetchStatus {
     Ready,
-    Processing,
+    Queued,
+    Running,
     Error,
 }
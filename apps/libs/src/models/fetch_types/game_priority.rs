@@ -0,0 +1,73 @@
+use async_graphql::InputObject;
+use serde::{Deserialize, Serialize};
+
+use crate::models::schemas::GameSchema;
+
+fn default_weight() -> usize {
+    1
+}
+
+/// Prioritises the games of a team, a tournament or a single game within a request.
+///
+/// `target` is matched against the home and away team, the tournament name and the game ID of
+/// every requested game. Matching games count `weight` times towards the coverage of a combination
+/// instead of once, and must be covered by every returned combination if `must_cover` is set.
+///
+/// Without a price or package cap, every combination covers all offered games anyway. The weights
+/// then decide which packages the greedy search tries first.
+#[derive(
+    InputObject, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug,
+)]
+pub struct GamePriority {
+    pub target: String,
+    #[graphql(default = 1)]
+    #[graphql(validator(minimum = 1, maximum = 100))]
+    #[serde(default = "default_weight")]
+    pub weight: usize,
+    #[graphql(default)]
+    #[serde(default)]
+    pub must_cover: bool,
+}
+
+impl GamePriority {
+    pub fn new(target: &str, weight: usize, must_cover: bool) -> Self {
+        GamePriority {
+            target: target.to_string(),
+            weight,
+            must_cover,
+        }
+    }
+
+    /// Returns whether the priority applies to the given game.
+    pub fn matches(&self, game: &GameSchema) -> bool {
+        self.target == game.team_home
+            || self.target == game.team_away
+            || self.target == game.tournament_name
+            || self.target == game.game_id.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::oid;
+
+    #[test]
+    fn test_matches_team_tournament_and_game_id() {
+        let game = GameSchema {
+            id: oid::ObjectId::new(),
+            game_id: 42,
+            team_away: "TEAM A".to_string(),
+            team_home: "TEAM B".to_string(),
+            starts_at: "2024-06-14 19:00:00".to_string(),
+            tournament_name: "TOURNAMENT X".to_string(),
+        };
+
+        assert!(GamePriority::new("TEAM A", 2, false).matches(&game));
+        assert!(GamePriority::new("TEAM B", 2, false).matches(&game));
+        assert!(GamePriority::new("TOURNAMENT X", 2, false).matches(&game));
+        assert!(GamePriority::new("42", 2, false).matches(&game));
+        assert!(!GamePriority::new("TEAM C", 2, false).matches(&game));
+        assert!(!GamePriority::new("4", 2, false).matches(&game));
+    }
+}
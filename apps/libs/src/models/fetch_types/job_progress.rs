@@ -0,0 +1,12 @@
+use async_graphql::SimpleObject;
+use serde::Serialize;
+
+/// The progress of a running job.
+#[derive(SimpleObject, Serialize, Clone, PartialEq, Debug)]
+pub struct JobProgress {
+    /// The number of search nodes explored so far.
+    pub explored_nodes: usize,
+    /// The billed monthly cost in cents of the cheapest cover found so far, if the search has
+    /// found one yet.
+    pub best_cost_so_far: Option<usize>,
+}
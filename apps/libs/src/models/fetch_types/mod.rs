@@ -1,7 +1,23 @@
+mod billing_option;
+mod coverage_requirement;
 mod fetch_options;
 mod fetch_result;
 mod fetch_status;
+mod game_priority;
+mod job_progress;
+mod package_filter;
+mod pricing_model;
+mod solver_mode;
+mod uncovered_reason;
 
+pub use billing_option::BillingOption;
+pub use coverage_requirement::CoverageRequirement;
 pub use fetch_options::FetchOptions;
 pub use fetch_result::FetchResult;
 pub use fetch_status::FetchStatus;
+pub use game_priority::GamePriority;
+pub use job_progress::JobProgress;
+pub use package_filter::PackageFilter;
+pub use pricing_model::PricingModel;
+pub use solver_mode::SolverMode;
+pub use uncovered_reason::UncoveredReason;
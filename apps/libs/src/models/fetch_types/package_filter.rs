@@ -0,0 +1,188 @@
+use std::hash::{Hash, Hasher};
+
+use async_graphql::InputObject;
+use mongodb::bson::{doc, Document, Regex};
+use serde::{Deserialize, Serialize};
+
+/// Restricts the streaming packages the solvers may choose from.
+///
+/// Packages are selected by their `streaming_package_id` or by name patterns, which match
+/// case-insensitively anywhere in the package name. Excluded packages never reach the solvers,
+/// while required packages are part of every combination. If a package is both excluded and
+/// required, the exclusion wins.
+#[derive(InputObject, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct PackageFilter {
+    #[graphql(default)]
+    #[serde(default)]
+    pub required_package_ids: Vec<usize>,
+    #[graphql(default)]
+    #[serde(default)]
+    pub required_name_patterns: Vec<String>,
+    #[graphql(default)]
+    #[serde(default)]
+    pub excluded_package_ids: Vec<usize>,
+    #[graphql(default)]
+    #[serde(default)]
+    pub excluded_name_patterns: Vec<String>,
+}
+
+impl PackageFilter {
+    /// Returns whether the package with the given ID and name has to be part of every combination.
+    pub fn requires(&self, package_id: usize, name: &str) -> bool {
+        self.required_package_ids.contains(&package_id)
+            || matches_any(&self.required_name_patterns, name)
+    }
+
+    /// Returns whether the package with the given ID and name is excluded, matching the same
+    /// packages as the [`PackageFilter::exclusion_conditions`].
+    pub fn excludes(&self, package_id: usize, name: &str) -> bool {
+        self.excluded_package_ids.contains(&package_id)
+            || matches_any(&self.excluded_name_patterns, name)
+    }
+
+    /// Builds the conditions a package document has to meet to not be excluded, to be merged into
+    /// a `$match` stage.
+    pub fn exclusion_conditions(&self) -> Document {
+        let mut conditions = Document::new();
+
+        if !self.excluded_package_ids.is_empty() {
+            let package_ids: Vec<u32> = self
+                .excluded_package_ids
+                .iter()
+                .map(|&id| id as u32)
+                .collect();
+            conditions.insert("streaming_package_id", doc! { "$nin": package_ids });
+        }
+
+        if !self.excluded_name_patterns.is_empty() {
+            let pattern = self
+                .excluded_name_patterns
+                .iter()
+                .map(|p| regex::escape(p))
+                .collect::<Vec<String>>()
+                .join("|");
+            conditions.insert(
+                "name",
+                doc! {
+                    "$not": Regex {
+                        pattern,
+                        options: "i".to_string(),
+                    }
+                },
+            );
+        }
+
+        conditions
+    }
+}
+
+fn matches_any(patterns: &[String], name: &str) -> bool {
+    let name = name.to_lowercase();
+    patterns
+        .iter()
+        .any(|pattern| name.contains(&pattern.to_lowercase()))
+}
+
+/// Hashes the filter independently of the order of its lists and the case of its patterns.
+impl Hash for PackageFilter {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        fn canonical_ids(ids: &[usize]) -> Vec<usize> {
+            let mut ids = ids.to_vec();
+            ids.sort();
+            ids.dedup();
+            ids
+        }
+
+        fn canonical_patterns(patterns: &[String]) -> Vec<String> {
+            let mut patterns: Vec<String> = patterns.iter().map(|p| p.to_lowercase()).collect();
+            patterns.sort();
+            patterns.dedup();
+            patterns
+        }
+
+        canonical_ids(&self.required_package_ids).hash(state);
+        canonical_patterns(&self.required_name_patterns).hash(state);
+        canonical_ids(&self.excluded_package_ids).hash(state);
+        canonical_patterns(&self.excluded_name_patterns).hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::DefaultHasher;
+
+    use super::*;
+
+    fn hash(filter: &PackageFilter) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        filter.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_requires() {
+        let filter = PackageFilter {
+            required_package_ids: vec![3],
+            required_name_patterns: vec!["sky".to_string()],
+            ..PackageFilter::default()
+        };
+
+        assert!(filter.requires(3, "MagentaTV"));
+        assert!(filter.requires(4, "Sky Sport"));
+        assert!(!filter.requires(5, "DAZN"));
+    }
+
+    #[test]
+    fn test_excludes() {
+        let filter = PackageFilter {
+            excluded_package_ids: vec![3],
+            excluded_name_patterns: vec!["sky".to_string()],
+            ..PackageFilter::default()
+        };
+
+        assert!(filter.excludes(3, "MagentaTV"));
+        assert!(filter.excludes(4, "Sky Sport"));
+        assert!(!filter.excludes(5, "DAZN"));
+        assert!(!PackageFilter::default().excludes(3, "Sky Sport"));
+    }
+
+    #[test]
+    fn test_exclusion_conditions() {
+        assert!(PackageFilter::default().exclusion_conditions().is_empty());
+
+        let filter = PackageFilter {
+            excluded_package_ids: vec![1, 2],
+            excluded_name_patterns: vec!["sky".to_string(), "a.b".to_string()],
+            ..PackageFilter::default()
+        };
+
+        assert_eq!(
+            filter.exclusion_conditions(),
+            doc! {
+                "streaming_package_id": { "$nin": [1, 2] },
+                "name": { "$not": Regex { pattern: "sky|a\\.b".to_string(), options: "i".to_string() } },
+            }
+        );
+    }
+
+    #[test]
+    fn test_hash_ignores_order_and_case() {
+        let a = PackageFilter {
+            excluded_package_ids: vec![2, 1],
+            excluded_name_patterns: vec!["Sky".to_string(), "DAZN".to_string()],
+            ..PackageFilter::default()
+        };
+        let b = PackageFilter {
+            excluded_package_ids: vec![1, 2],
+            excluded_name_patterns: vec!["dazn".to_string(), "sky".to_string()],
+            ..PackageFilter::default()
+        };
+        let c = PackageFilter {
+            required_package_ids: vec![1, 2],
+            ..PackageFilter::default()
+        };
+
+        assert_eq!(hash(&a), hash(&b));
+        assert_ne!(hash(&b), hash(&c));
+    }
+}
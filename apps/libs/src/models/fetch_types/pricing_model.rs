@@ -0,0 +1,71 @@
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+
+use super::BillingOption;
+use crate::models::dtos::BestCombinationSubsetDto;
+
+/// Selects which prices the solvers minimize.
+///
+/// * `Monthly` - Every package is billed with its monthly-cancellable subscription. Packages
+///   without one can't be booked.
+/// * `Yearly` - Every package is billed with its yearly subscription.
+/// * `CheapestOfBoth` - Every package is billed with whichever of both subscriptions is cheaper
+///   per month.
+#[derive(Enum, Copy, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize, Debug)]
+pub enum PricingModel {
+    #[default]
+    Monthly,
+    Yearly,
+    CheapestOfBoth,
+}
+
+impl PricingModel {
+    /// Returns the billing option the pricing model chooses for the given package.
+    pub fn billing_for(&self, subset: &BestCombinationSubsetDto) -> BillingOption {
+        match self {
+            PricingModel::Monthly => BillingOption::Monthly,
+            PricingModel::Yearly => BillingOption::Yearly,
+            PricingModel::CheapestOfBoth => match subset.monthly_price_cents {
+                Some(monthly) if monthly <= subset.monthly_price_yearly_subscription_in_cents => {
+                    BillingOption::Monthly
+                }
+                _ => BillingOption::Yearly,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn test_billing_for() {
+        let monthly_cheaper = BestCombinationSubsetDto::new(1, "S1", BTreeSet::new(), Some(5), 10);
+        let yearly_cheaper = BestCombinationSubsetDto::new(2, "S2", BTreeSet::new(), Some(15), 10);
+        let yearly_only = BestCombinationSubsetDto::new(3, "S3", BTreeSet::new(), None, 10);
+
+        assert_eq!(
+            PricingModel::Monthly.billing_for(&yearly_cheaper),
+            BillingOption::Monthly
+        );
+        assert_eq!(
+            PricingModel::Yearly.billing_for(&monthly_cheaper),
+            BillingOption::Yearly
+        );
+        assert_eq!(
+            PricingModel::CheapestOfBoth.billing_for(&monthly_cheaper),
+            BillingOption::Monthly
+        );
+        assert_eq!(
+            PricingModel::CheapestOfBoth.billing_for(&yearly_cheaper),
+            BillingOption::Yearly
+        );
+        assert_eq!(
+            PricingModel::CheapestOfBoth.billing_for(&yearly_only),
+            BillingOption::Yearly
+        );
+    }
+}
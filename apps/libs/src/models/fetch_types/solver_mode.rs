@@ -0,0 +1,34 @@
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+
+/// Selects the set cover algorithm the worker runs for a request.
+///
+/// * `Greedy` - The ratio-guided recursive backtracking search. Fast, but it returns the
+///   first covers it finds, which are not guaranteed to be the cheapest ones. The worker can be
+///   configured to run another registered solver instead.
+/// * `Exact` - A branch-and-bound search with lower-bound pruning that returns the cheapest
+///   covers ranked by price and proves their optimality, unless it runs into the worker's
+///   deadline first.
+/// * `Scheduled` - Groups the games by calendar month and computes the cheapest cover for every
+///   month with monthly-cancellable subscriptions. Returns a single combination with the
+///   month-by-month plan, compared against subscribing yearly for the whole season.
+/// * `ParetoCoverage` - Returns every combination for which no other one covers as many games for
+///   less, ranked by ascending price, to trade off the price against the coverage. The `limit`
+///   doesn't apply to the frontier.
+/// * `ParetoLiveCoverage` - Like `ParetoCoverage`, but only counts the games a combination streams
+///   live as covered.
+///
+/// The `solver` of the fetch options selects a registered solver by name and takes precedence over
+/// the `Greedy` and `Exact` modes. With a maximum monthly price or number of packages, both modes
+/// run the budgeted branch-and-bound search instead, which maximizes the coverage within the caps.
+/// The caps can't be combined with a `solver` or the `Scheduled` mode, see
+/// [`FetchOptions::validate`](super::FetchOptions::validate).
+#[derive(Enum, Copy, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize, Debug)]
+pub enum SolverMode {
+    #[default]
+    Greedy,
+    Exact,
+    Scheduled,
+    ParetoCoverage,
+    ParetoLiveCoverage,
+}
@@ -0,0 +1,15 @@
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+
+/// Why a requested game isn't covered by a combination.
+///
+/// * `NotOffered` - No package offers the game at all.
+/// * `ExcludedByOptions` - Some package offers the game, but the options of the request rule all of
+///   them out, e.g. by excluding them, requiring the game live or billing them monthly.
+/// * `NotInCombination` - Some package offers the game, but none of the combination's packages.
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, Debug)]
+pub enum UncoveredReason {
+    NotOffered,
+    ExcludedByOptions,
+    NotInCombination,
+}
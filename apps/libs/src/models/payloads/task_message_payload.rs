@@ -1,11 +1,79 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::caching::CompositeKey;
+use crate::{
+    caching::CompositeKey,
+    models::{
+        fetch_types::{CoverageRequirement, GamePriority, PackageFilter, PricingModel, SolverMode},
+        schemas::GameSchema,
+    },
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TaskMessagePayload {
     pub game_ids: Vec<usize>,
     pub limit: usize,
+    #[serde(default)]
+    pub mode: SolverMode,
+    #[serde(default)]
+    pub coverage: CoverageRequirement,
+    #[serde(default)]
+    pub max_monthly_price_cents: Option<usize>,
+    #[serde(default)]
+    pub max_packages: Option<usize>,
+    #[serde(default)]
+    pub pricing: PricingModel,
+    #[serde(default)]
+    pub priorities: Vec<GamePriority>,
+    #[serde(default)]
+    pub solver: Option<String>,
+    #[serde(default)]
+    pub owned_package_ids: Vec<usize>,
+    #[serde(default)]
+    pub package_filter: PackageFilter,
+    /// Weights of the prioritised games, as resolved by [`TaskMessagePayload::resolve_priorities`].
+    /// Games without an entry weigh `1`.
+    #[serde(default)]
+    pub game_weights: BTreeMap<usize, usize>,
+    /// Games that every combination has to cover.
+    #[serde(default)]
+    pub must_cover_ids: Vec<usize>,
+    #[serde(default)]
+    pub include_game_coverage: bool,
+}
+
+impl TaskMessagePayload {
+    /// Resolves the `priorities` into per-game weights and must-cover constraints.
+    ///
+    /// Only games of the payload are considered. If several priorities apply to the same game, the
+    /// highest weight wins, and a single must-cover priority makes it a must-cover game.
+    ///
+    /// # Arguments
+    ///
+    /// * `games` - The games of the payload, used to match teams and tournaments to game IDs.
+    pub fn resolve_priorities(&mut self, games: &[GameSchema]) {
+        self.game_weights.clear();
+        self.must_cover_ids.clear();
+
+        for game in games {
+            let game_id = game.game_id as usize;
+            if !self.game_ids.contains(&game_id) {
+                continue;
+            }
+
+            for priority in self.priorities.iter().filter(|p| p.matches(game)) {
+                let weight = self.game_weights.entry(game_id).or_insert(1);
+                *weight = (*weight).max(priority.weight);
+
+                if priority.must_cover && !self.must_cover_ids.contains(&game_id) {
+                    self.must_cover_ids.push(game_id);
+                }
+            }
+        }
+
+        self.must_cover_ids.sort();
+    }
 }
 
 impl From<CompositeKey> for TaskMessagePayload {
@@ -13,6 +81,62 @@ impl From<CompositeKey> for TaskMessagePayload {
         TaskMessagePayload {
             game_ids: o.ids,
             limit: o.opts.limit,
+            mode: o.opts.mode,
+            coverage: o.opts.coverage,
+            max_monthly_price_cents: o.opts.max_monthly_price_cents,
+            max_packages: o.opts.max_packages,
+            pricing: o.opts.pricing,
+            priorities: o.opts.priorities,
+            solver: o.opts.solver,
+            owned_package_ids: o.opts.owned_package_ids,
+            package_filter: o.opts.package_filter,
+            game_weights: BTreeMap::new(),
+            must_cover_ids: Vec::new(),
+            include_game_coverage: o.opts.include_game_coverage,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fetch_types::FetchOptions;
+    use mongodb::bson::oid;
+
+    fn game(game_id: u32, team_home: &str, team_away: &str, tournament_name: &str) -> GameSchema {
+        GameSchema {
+            id: oid::ObjectId::new(),
+            game_id,
+            team_away: team_away.to_string(),
+            team_home: team_home.to_string(),
+            starts_at: "2024-06-14 19:00:00".to_string(),
+            tournament_name: tournament_name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_priorities() {
+        let opts = FetchOptions {
+            priorities: vec![
+                GamePriority::new("TEAM A", 3, false),
+                GamePriority::new("CUP", 5, false),
+                GamePriority::new("3", 1, true),
+            ],
+            ..FetchOptions::new(1)
+        };
+        let mut payload = TaskMessagePayload::from(CompositeKey::new(vec![1, 2, 3], opts));
+
+        payload.resolve_priorities(&[
+            game(1, "TEAM A", "TEAM B", "LEAGUE"),
+            game(2, "TEAM C", "TEAM A", "CUP"),
+            game(3, "TEAM B", "TEAM C", "LEAGUE"),
+            game(4, "TEAM A", "TEAM C", "CUP"),
+        ]);
+
+        assert_eq!(
+            payload.game_weights,
+            BTreeMap::from([(1, 3), (2, 5), (3, 1)])
+        );
+        assert_eq!(payload.must_cover_ids, vec![3]);
+    }
+}
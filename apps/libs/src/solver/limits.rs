@@ -0,0 +1,131 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Number of explored search nodes between two deadline checks.
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
+/// The wall-clock deadline and explored-node budget shared by all searches of a request.
+///
+/// The solvers account for every node they explore via [`SearchLimits::explore`]. Once the
+/// deadline has passed or the node budget is used up, the limits are exhausted for good and every
+/// search returns the best results it has found so far. The limits can be shared between the
+/// threads of a parallel search.
+///
+/// The covering searches also record the cost of the covers they find, so the explored nodes and
+/// the cheapest cost found so far can be reported as the progress of a request.
+#[derive(Debug)]
+pub struct SearchLimits {
+    deadline: Instant,
+    max_nodes: usize,
+    explored_nodes: AtomicUsize,
+    exhausted: AtomicBool,
+    /// The cost of the cheapest cover found so far, `usize::MAX` if none has been found.
+    best_cost: AtomicUsize,
+    /// The cost of the packages which are part of every cover, but not searched.
+    base_cost: AtomicUsize,
+}
+
+impl SearchLimits {
+    pub fn new(timeout: Duration, max_nodes: usize) -> Self {
+        SearchLimits {
+            deadline: Instant::now() + timeout,
+            max_nodes,
+            explored_nodes: AtomicUsize::new(0),
+            exhausted: AtomicBool::new(false),
+            best_cost: AtomicUsize::new(usize::MAX),
+            base_cost: AtomicUsize::new(0),
+        }
+    }
+
+    /// Accounts for one explored node. The clock is only read every [`DEADLINE_CHECK_INTERVAL`]
+    /// nodes, starting with the first one.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the limits are exhausted and the search has to stop.
+    pub fn explore(&self) -> bool {
+        if self.is_exhausted() {
+            return false;
+        }
+
+        let explored_nodes = self.explored_nodes.fetch_add(1, Ordering::Relaxed) + 1;
+        if explored_nodes > self.max_nodes
+            || ((explored_nodes - 1).is_multiple_of(DEADLINE_CHECK_INTERVAL)
+                && Instant::now() >= self.deadline)
+        {
+            self.exhausted.store(true, Ordering::Relaxed);
+            return false;
+        }
+
+        true
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted.load(Ordering::Relaxed)
+    }
+
+    pub fn explored_nodes(&self) -> usize {
+        self.explored_nodes.load(Ordering::Relaxed)
+    }
+
+    /// Records the billed cost of a cover a search has found. Only the cheapest cost is kept.
+    pub fn record_cost(&self, cost: usize) {
+        self.best_cost.fetch_min(cost, Ordering::Relaxed);
+    }
+
+    /// Sets the billed cost of the packages which are part of every cover without being searched,
+    /// e.g. the ones forced by a reduction, to add it to the recorded costs.
+    pub fn set_base_cost(&self, cost: usize) {
+        self.base_cost.store(cost, Ordering::Relaxed);
+    }
+
+    /// Returns the billed cost of the cheapest cover found so far including the base cost, or
+    /// `None` if no cover has been recorded yet.
+    pub fn best_cost(&self) -> Option<usize> {
+        match self.best_cost.load(Ordering::Relaxed) {
+            usize::MAX => None,
+            cost => Some(cost + self.base_cost.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_budget() {
+        let limits = SearchLimits::new(Duration::from_secs(10), 2);
+
+        assert!(limits.explore());
+        assert!(limits.explore());
+        assert!(!limits.explore());
+        assert!(!limits.explore());
+        assert!(limits.is_exhausted());
+        assert_eq!(limits.explored_nodes(), 3);
+    }
+
+    #[test]
+    fn test_best_cost() {
+        let limits = SearchLimits::new(Duration::from_secs(10), usize::MAX);
+        assert_eq!(limits.best_cost(), None);
+
+        limits.record_cost(30);
+        limits.record_cost(20);
+        limits.record_cost(25);
+        assert_eq!(limits.best_cost(), Some(20));
+
+        limits.set_base_cost(5);
+        assert_eq!(limits.best_cost(), Some(25));
+    }
+
+    #[test]
+    fn test_deadline() {
+        let limits = SearchLimits::new(Duration::ZERO, usize::MAX);
+
+        assert!(!limits.explore());
+        assert!(limits.is_exhausted());
+    }
+}
@@ -0,0 +1,7 @@
+mod limits;
+mod registry;
+mod set_cover_solver;
+
+pub use limits::SearchLimits;
+pub use registry::SolverRegistry;
+pub use set_cover_solver::SetCoverSolver;
@@ -0,0 +1,108 @@
+use super::SetCoverSolver;
+
+/// The set cover solvers available to the worker, looked up by their name.
+#[derive(Default)]
+pub struct SolverRegistry {
+    solvers: Vec<Box<dyn SetCoverSolver>>,
+}
+
+impl SolverRegistry {
+    pub fn new() -> Self {
+        SolverRegistry::default()
+    }
+
+    /// Adds a solver to the registry, replacing any solver registered under the same name.
+    pub fn register(mut self, solver: impl SetCoverSolver + 'static) -> Self {
+        self.solvers.retain(|s| s.name() != solver.name());
+        self.solvers.push(Box::new(solver));
+        self
+    }
+
+    /// Looks up the solver registered under `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing the available solvers if no solver has been registered under `name`.
+    pub fn get(&self, name: &str) -> anyhow::Result<&dyn SetCoverSolver> {
+        self.iter().find(|s| s.name() == name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown solver '{}', expected one of {:?}",
+                name,
+                self.names()
+            )
+        })
+    }
+
+    /// Returns the names of all registered solvers, in the order they have been registered.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.iter().map(|s| s.name()).collect()
+    }
+
+    /// Iterates over all registered solvers, in the order they have been registered.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn SetCoverSolver> {
+        self.solvers.iter().map(|s| s.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use super::*;
+    use crate::{
+        models::dtos::{BestCombinationDto, BestCombinationSubsetDto},
+        solver::SearchLimits,
+    };
+
+    struct FixedSolver {
+        name: &'static str,
+        price: usize,
+    }
+
+    impl SetCoverSolver for FixedSolver {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn solve(
+            &self,
+            _universe: &BTreeSet<usize>,
+            _subsets: &[BestCombinationSubsetDto],
+            _limit: usize,
+            _weights: &BTreeMap<usize, usize>,
+            _limits: &SearchLimits,
+        ) -> Vec<BestCombinationDto> {
+            vec![BestCombinationDto::new(vec![], self.price, 0, 0, 0)]
+        }
+    }
+
+    #[test]
+    fn test_solver_registry() {
+        let registry = SolverRegistry::new()
+            .register(FixedSolver {
+                name: "a",
+                price: 1,
+            })
+            .register(FixedSolver {
+                name: "b",
+                price: 2,
+            })
+            .register(FixedSolver {
+                name: "a",
+                price: 3,
+            });
+
+        assert_eq!(registry.names(), vec!["b", "a"]);
+
+        let solver = registry.get("a").unwrap();
+        let limits = SearchLimits::new(std::time::Duration::from_secs(1), 1);
+        let results = solver.solve(&BTreeSet::new(), &[], 1, &BTreeMap::new(), &limits);
+        assert_eq!(results[0].combined_monthly_price_cents, 3);
+
+        let err = registry.get("c").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Unknown solver 'c', expected one of [\"b\", \"a\"]"
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::models::dtos::{BestCombinationDto, BestCombinationSubsetDto};
+
+use super::SearchLimits;
+
+/// A set cover algorithm which computes combinations of streaming packages covering a set of games.
+///
+/// # Overview
+///
+/// Implementations are registered in a [`SolverRegistry`](super::SolverRegistry) under their
+/// [`SetCoverSolver::name`], which is how a request or the configuration selects them. The
+/// subsets are expected to be filtered and billed already, so a solver only decides which of them
+/// to combine.
+pub trait SetCoverSolver: Send + Sync {
+    /// The unique name the solver is registered and selected by.
+    fn name(&self) -> &'static str;
+
+    /// Computes up to `limit` combinations of `subsets` covering the `universe`.
+    ///
+    /// # Arguments
+    ///
+    /// * `universe` - A `BTreeSet<usize>` representing all game IDs that must be covered.
+    /// * `subsets` - A slice of `BestCombinationSubsetDto` representing candidate streaming packages.
+    /// * `limit` - The maximum number of combinations to return.
+    /// * `weights` - The weights of the prioritised games, games without one weigh `1`. Solvers
+    ///   which always cover every offered game at the lowest price may ignore them.
+    /// * `limits` - The deadline and node budget of the search.
+    ///
+    /// # Returns
+    ///
+    /// `Vec<BestCombinationDto>`: The combinations found, indexed in the order of their rank.
+    ///
+    fn solve(
+        &self,
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        limit: usize,
+        weights: &BTreeMap<usize, usize>,
+        limits: &SearchLimits,
+    ) -> Vec<BestCombinationDto>;
+
+    /// Whether the solver finds equally good combinations on an instance shrunk by the classic set
+    /// cover reductions, i.e. without dominated packages, with sole providers forced upfront and
+    /// with games offered by the same packages collapsed into one. Heuristics which count the
+    /// uncovered games can pick different packages on such an instance.
+    fn supports_reductions(&self) -> bool {
+        false
+    }
+
+    /// Whether the solver makes use of the warm start passed to [`SetCoverSolver::solve_warm`].
+    fn supports_warm_start(&self) -> bool {
+        false
+    }
+
+    /// Computes combinations like [`SetCoverSolver::solve`], but seeds the search with
+    /// `warm_start`, the package IDs of a cover of a similar universe. Solvers which can't make
+    /// use of a known cover ignore it.
+    fn solve_warm(
+        &self,
+        universe: &BTreeSet<usize>,
+        subsets: &[BestCombinationSubsetDto],
+        limit: usize,
+        weights: &BTreeMap<usize, usize>,
+        limits: &SearchLimits,
+        _warm_start: &[usize],
+    ) -> Vec<BestCombinationDto> {
+        self.solve(universe, subsets, limit, weights, limits)
+    }
+}
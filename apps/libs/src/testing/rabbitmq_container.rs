@@ -34,7 +34,8 @@ mod tests {
         let channel = messaging::get_channel(&uri).await.unwrap();
         messaging::init_mq(&channel, queue_name).await.unwrap();
 
-        let consumer = messaging::create_consumer(&channel, queue_name, "TEST_CONSUMER")
+        let job_queue = messaging::job_queue_name(queue_name);
+        let consumer = messaging::create_consumer(&channel, &job_queue, "TEST_CONSUMER")
             .await
             .unwrap();
 
@@ -51,7 +52,7 @@ mod tests {
 
         let payload = "Hello World!".to_string();
 
-        messaging::enqueue_job(&channel, queue_name, &payload)
+        messaging::enqueue_job(&channel, &job_queue, &payload)
             .await
             .unwrap();
     }
@@ -50,10 +50,10 @@ mod tests {
 
         let key = CompositeKey {
             ids: vec![1, 2, 3],
-            opts: FetchOptions { limit: 3 },
+            opts: FetchOptions::new(3),
         };
         let value = "Hello World!".to_string();
-        caching::cache_entry(&redis_client, &key, CacheValue::Data(value.clone()))
+        caching::cache_entry(&redis_client, &key, CacheValue::Completed(value.clone()))
             .await
             .unwrap();
 
@@ -61,6 +61,6 @@ mod tests {
             .await
             .unwrap();
         assert!(cached.is_some());
-        assert_eq!(cached.unwrap().value, CacheValue::Data(value));
+        assert_eq!(cached.unwrap().value, CacheValue::Completed(value));
     }
 }
@@ -28,7 +28,7 @@ async fn test_int_cache() -> anyhow::Result<()> {
         1,
     )];
 
-    caching::cache_entry(&redis_client, &key, CacheValue::Data(value.clone()))
+    caching::cache_entry(&redis_client, &key, CacheValue::Completed(value.clone()))
         .await
         .unwrap();
 
@@ -40,7 +40,97 @@ async fn test_int_cache() -> anyhow::Result<()> {
     assert!(retrieved_entry.is_some());
     let entry = retrieved_entry.unwrap();
     assert_eq!(entry.key, key);
-    assert_eq!(entry.value, CacheValue::Data(value));
+    assert_eq!(entry.value, CacheValue::Completed(value));
+
+    Ok(())
+}
+
+#[ignore = "CI needs testcontainer configuration in shell"]
+#[tokio::test]
+async fn test_int_warm_start() -> anyhow::Result<()> {
+    dotenv::dotenv().ok();
+
+    let url = testing::init_redis_container().await.unwrap();
+    let redis_client = caching::init_redis(&url).await.unwrap();
+
+    let solved = CompositeKey::new(vec![1, 2, 3, 4], FetchOptions::new(1));
+    caching::record_warm_start(&redis_client, &solved, vec![4, 13])
+        .await
+        .unwrap();
+
+    let similar = CompositeKey::new(vec![1, 2, 3, 4, 5], FetchOptions::new(1));
+    let warm_start = caching::find_warm_start(&redis_client, &similar, 0.2)
+        .await
+        .unwrap();
+    assert_eq!(
+        warm_start,
+        Some(caching::WarmStart {
+            game_ids: vec![1, 2, 3, 4],
+            package_ids: vec![4, 13],
+        })
+    );
+
+    let distant = CompositeKey::new(vec![1, 2, 5, 6], FetchOptions::new(1));
+    assert!(caching::find_warm_start(&redis_client, &distant, 0.2)
+        .await
+        .unwrap()
+        .is_none());
+
+    let other_options = CompositeKey::new(vec![1, 2, 3, 4], FetchOptions::new(2));
+    assert!(caching::find_warm_start(&redis_client, &other_options, 0.2)
+        .await
+        .unwrap()
+        .is_none());
+
+    Ok(())
+}
+
+#[ignore = "CI needs testcontainer configuration in shell"]
+#[tokio::test]
+async fn test_int_cache_updates() -> anyhow::Result<()> {
+    use futures::StreamExt;
+
+    dotenv::dotenv().ok();
+
+    let url = testing::init_redis_container().await.unwrap();
+    let redis_client = caching::init_redis(&url).await.unwrap();
+
+    let key = CompositeKey::new(vec![1, 2, 3], FetchOptions::new(1));
+    let other_key = CompositeKey::new(vec![1, 2], FetchOptions::new(1));
+    let mut updates = Box::pin(
+        caching::subscribe_updates::<CompositeKey, Vec<usize>>(&redis_client, &key)
+            .await
+            .unwrap(),
+    );
+
+    caching::cache_entry(&redis_client, &other_key, CacheValue::Completed(vec![7]))
+        .await
+        .unwrap();
+    caching::cache_entry(
+        &redis_client,
+        &key,
+        CacheValue::<Vec<usize>>::Running {
+            explored_nodes: 42,
+            best_cost_so_far: Some(30),
+        },
+    )
+    .await
+    .unwrap();
+    caching::cache_entry(&redis_client, &key, CacheValue::Completed(vec![4, 13]))
+        .await
+        .unwrap();
+
+    let update = updates.next().await.unwrap().unwrap();
+    assert_eq!(update.key, key);
+    assert_eq!(
+        update.value,
+        CacheValue::Running {
+            explored_nodes: 42,
+            best_cost_so_far: Some(30),
+        }
+    );
+    let update = updates.next().await.unwrap().unwrap();
+    assert_eq!(update.value, CacheValue::Completed(vec![4, 13]));
 
     Ok(())
 }
@@ -1,23 +1,49 @@
 use benchmarks::*;
+use best_combination_worker::solver_registry;
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use std::time::Duration;
+use libs::solver::SearchLimits;
+use std::{collections::BTreeMap, time::Duration};
 
+/// Compares the initial recursive search, which recomputes the covered games as a `BTreeSet` at
+/// every node, with the bitset coverage of the registered `recursive` solver.
 fn bench_recursive_set_cover(c: &mut Criterion) {
     let (universe, subsets) = util::build_test_data();
+    let (_, solver_subsets) = util::build_solver_test_data();
+    let registry = solver_registry(false);
+    let solver = registry.get("recursive").unwrap();
 
     let mut group = c.benchmark_group("recursive_set_cover");
     group.measurement_time(Duration::from_secs(30));
     group.sample_size(50);
 
-    for i in 1..=10 {
-        group.bench_function(BenchmarkId::new("recursive", i), |b| {
+    for limit in [1, 4, 10] {
+        group.bench_function(BenchmarkId::new("btreeset", limit), |b| {
             b.iter(|| {
-                let result =
-                    recursive_set_covers(black_box(&universe), black_box(&subsets), black_box(i));
+                let result = recursive_set_covers(
+                    black_box(&universe),
+                    black_box(&subsets),
+                    black_box(limit),
+                );
+                black_box(result);
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("bitset", limit), |b| {
+            b.iter(|| {
+                let limits = SearchLimits::new(Duration::from_secs(60), usize::MAX);
+                let result = solver.solve(
+                    black_box(&universe),
+                    black_box(&solver_subsets),
+                    black_box(limit),
+                    &BTreeMap::new(),
+                    &limits,
+                );
                 black_box(result);
             })
         });
     }
+
+    group.finish();
 }
 
 criterion_group!(benches, bench_recursive_set_cover);
@@ -0,0 +1,38 @@
+use benchmarks::*;
+use best_combination_worker::solver_registry;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use libs::solver::SearchLimits;
+use std::{collections::BTreeMap, time::Duration};
+
+fn bench_solver_registry(c: &mut Criterion) {
+    let (universe, subsets) = util::build_solver_test_data();
+    let registry = solver_registry(false);
+
+    let mut group = c.benchmark_group("solver_registry");
+    group.measurement_time(Duration::from_secs(30));
+    group.sample_size(50);
+
+    for solver in registry.iter() {
+        // Input being the number of best combinations to retrieve
+        for limit in [1, 4, 10] {
+            group.bench_function(BenchmarkId::new(solver.name(), limit), |b| {
+                b.iter(|| {
+                    let limits = SearchLimits::new(Duration::from_secs(60), usize::MAX);
+                    let result = solver.solve(
+                        black_box(&universe),
+                        black_box(&subsets),
+                        black_box(limit),
+                        &BTreeMap::new(),
+                        &limits,
+                    );
+                    black_box(result);
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_solver_registry);
+criterion_main!(benches);
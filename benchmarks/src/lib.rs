@@ -1,6 +1,4 @@
-mod iterative;
 mod recursive;
 pub mod util;
 
-pub use iterative::iterative_set_cover;
 pub use recursive::recursive_set_covers;
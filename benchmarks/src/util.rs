@@ -1,5 +1,6 @@
 use std::{collections::BTreeSet, fs::File, io::BufReader};
 
+use libs::models::dtos::{BestCombinationElementDto, BestCombinationSubsetDto};
 use serde::Deserialize;
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
@@ -26,6 +27,24 @@ pub struct Subset {
     pub cost: usize,
 }
 
+impl From<&Subset> for BestCombinationSubsetDto {
+    /// Converts a benchmark subset into the package type of the registered solvers, offering every
+    /// game live and with highlights for `cost` with both subscriptions.
+    fn from(subset: &Subset) -> Self {
+        BestCombinationSubsetDto::new(
+            subset.id,
+            &format!("S{}", subset.id),
+            subset
+                .elements
+                .iter()
+                .map(|&game_id| BestCombinationElementDto::new(game_id, "", 1, 1))
+                .collect(),
+            Some(subset.cost),
+            subset.cost,
+        )
+    }
+}
+
 #[derive(Deserialize)]
 pub struct UniverseWrapper {
     pub universe: BTreeSet<usize>,
@@ -52,9 +71,20 @@ pub fn build_test_data() -> (BTreeSet<usize>, Vec<Subset>) {
     (universe, subsets)
 }
 
+/// Loads the benchmark data like [`build_test_data`], with the subsets converted for the solvers of
+/// the [`SolverRegistry`](libs::solver::SolverRegistry).
+pub fn build_solver_test_data() -> (BTreeSet<usize>, Vec<BestCombinationSubsetDto>) {
+    let (universe, subsets) = build_test_data();
+    let subsets = subsets.iter().map(BestCombinationSubsetDto::from).collect();
+    (universe, subsets)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::{collections::BTreeMap, time::Duration};
+
     use super::*;
+    use libs::solver::SearchLimits;
 
     #[test]
     fn test_build_test_data() {
@@ -62,4 +92,46 @@ mod tests {
         assert!(!universe.is_empty());
         assert!(!subsets.is_empty());
     }
+
+    #[test]
+    fn test_registered_solvers_cover_universe() {
+        let (universe, subsets) = build_solver_test_data();
+        let registry = best_combination_worker::solver_registry(false);
+
+        let mut costs = Vec::new();
+        for solver in registry.iter() {
+            let limits = SearchLimits::new(Duration::from_secs(60), usize::MAX);
+            let results = solver.solve(&universe, &subsets, 1, &BTreeMap::new(), &limits);
+            assert_eq!(results.len(), 1, "{}", solver.name());
+            assert!(!results[0].timed_out, "{}", solver.name());
+            costs.push((solver.name(), results[0].combined_monthly_price_cents));
+        }
+
+        // The exact solver may find a cheaper cover than the greedy strategies, but never a
+        // more expensive one
+        let exact_cost = costs.iter().find(|(name, _)| *name == "exact").unwrap().1;
+        assert!(
+            costs.iter().all(|(_, cost)| exact_cost <= *cost),
+            "{:?}",
+            costs
+        );
+    }
+
+    #[test]
+    fn test_recursive_solver_matches_baseline() {
+        let (universe, subsets) = build_test_data();
+        let (_, solver_subsets) = build_solver_test_data();
+        let registry = best_combination_worker::solver_registry(false);
+        let solver = registry.get("recursive").unwrap();
+
+        let limits = SearchLimits::new(Duration::from_secs(60), usize::MAX);
+        let results = solver.solve(&universe, &solver_subsets, 1, &BTreeMap::new(), &limits);
+        let mut package_ids: Vec<usize> = results[0].packages.iter().map(|p| p.id).collect();
+        package_ids.sort();
+
+        assert_eq!(
+            crate::recursive_set_covers(&universe, &subsets, 1),
+            vec![package_ids]
+        );
+    }
 }
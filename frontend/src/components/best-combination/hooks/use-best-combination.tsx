@@ -43,7 +43,11 @@ const useBestCombination = (): UseBestCombinationReturn => {
                     });
                     setError(undefined);
                     return;
-                } else if (result?.status === BestCombinationStatus.PROCESSING && elapsedTime < TIMEOUT) {
+                } else if (
+                    (result?.status === BestCombinationStatus.QUEUED ||
+                        result?.status === BestCombinationStatus.RUNNING) &&
+                    elapsedTime < TIMEOUT
+                ) {
                     elapsedTime += POLL_INTERVAL;
                     setTimeout(fetchWithPolling, POLL_INTERVAL);
                 } else {
@@ -21,7 +21,8 @@ export interface BestCombination {
 
 export enum BestCombinationStatus {
     READY = 'READY',
-    PROCESSING = 'PROCESSING',
+    QUEUED = 'QUEUED',
+    RUNNING = 'RUNNING',
     ERROR = 'ERROR',
 }
 